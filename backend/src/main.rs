@@ -175,6 +175,7 @@ fn create_menus() -> Menu {
 fn on_event(evt: GlobalWindowEvent) {
     if let WindowEvent::Destroyed = evt.event() {
         info!("Stopping and destroying all tari containers");
+        tari_sdm_launchpad::tauri::bus_shutdown(&evt.window().app_handle());
         let docker = evt.window().state::<AppState>().docker.clone();
         let task = thread::spawn(move || block_on(shutdown_all_containers(DEFAULT_WORKSPACE_NAME, &docker)));
         drop(task.join());
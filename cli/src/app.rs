@@ -1,26 +1,40 @@
 use anyhow::Error;
 use crossterm::event::{Event, KeyCode};
-use tari_launchpad_protocol::launchpad::{Action, LaunchpadAction};
+use tari_launchpad_protocol::launchpad::{Action, LaunchpadAction, Reaction};
 use tari_sdm_launchpad::LaunchpadBus;
 use tokio::{
     select,
+    sync::mpsc,
     time::{sleep, Duration, Instant},
 };
 
-use crate::Dashboard;
+use crate::{passphrase::PASSPHRASE_ENV, Dashboard};
 
 pub struct App {
-    bus: LaunchpadBus,
+    incoming: mpsc::UnboundedSender<Action>,
+    outgoing: mpsc::UnboundedReceiver<Reaction>,
+    /// The app is attached to a daemon that keeps
+    /// the containers running when the app quits.
+    attached: bool,
     dashboard: Dashboard,
     last_render: Instant,
 }
 
 impl App {
-    pub fn init() -> Result<Self, Error> {
-        let bus = LaunchpadBus::start()?;
+    /// Attaches to the daemon if it's running or starts own bus.
+    pub async fn init() -> Result<Self, Error> {
+        let (incoming, outgoing, attached) = match attach().await {
+            Some((incoming, outgoing)) => (incoming, outgoing, true),
+            None => {
+                let bus = LaunchpadBus::start()?;
+                (bus.incoming, bus.outgoing, false)
+            },
+        };
         let dashboard = Dashboard::init()?;
         Ok(Self {
-            bus,
+            incoming,
+            outgoing,
+            attached,
             dashboard,
             last_render: Instant::now(),
         })
@@ -29,7 +43,14 @@ impl App {
     /// Connect to a bus.
     pub fn connect(&mut self) -> Result<(), Error> {
         let action = Action::Action(LaunchpadAction::Connect);
-        self.bus.incoming.send(action)?;
+        self.incoming.send(action)?;
+        if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+            let action = Action::Action(LaunchpadAction::UnlockSecrets(passphrase.into()));
+            self.incoming.send(action)?;
+        } else {
+            // Asks for the passphrase if the store is locked
+            self.dashboard.prompt_passphrase();
+        }
         Ok(())
     }
 
@@ -48,20 +69,30 @@ impl App {
         select! {
             _ = sleep(Duration::from_millis(800)) => {
             }
-            event = self.bus.outgoing.recv() => {
+            event = self.outgoing.recv() => {
                 if let Some(event) = event {
                     self.dashboard.process_delta(event);
+                } else {
+                    // The worker has stopped all the tasks
+                    self.dashboard.terminate();
                 }
             }
             event = self.dashboard.next_event() => {
                 if let Some(Event::Key(key)) = event {
-                    if let KeyCode::Char('q') = key.code {
-                        self.dashboard.terminate();
+                    if key.code == KeyCode::Char('q') && !self.dashboard.is_prompting() {
+                        if self.attached {
+                            // Detaches from the daemon
+                            self.dashboard.terminate();
+                        } else {
+                            let action = Action::Action(LaunchpadAction::Shutdown);
+                            if self.incoming.send(action).is_err() {
+                                self.dashboard.terminate();
+                            }
+                        }
                     }
-                    if let Some(new_session) = self.dashboard.process_key(key.code) {
-                        let event = LaunchpadAction::ChangeSession(new_session);
+                    if let Some(event) = self.dashboard.process_key(key.code) {
                         let action = Action::Action(event);
-                        self.bus.incoming.send(action)?;
+                        self.incoming.send(action)?;
                     }
                     self.dashboard.render()?;
                 }
@@ -74,3 +105,14 @@ impl App {
         Ok(())
     }
 }
+
+#[cfg(unix)]
+pub async fn attach() -> Option<(mpsc::UnboundedSender<Action>, mpsc::UnboundedReceiver<Reaction>)> {
+    let client = crate::daemon::try_attach().await?;
+    Some((client.incoming, client.outgoing))
+}
+
+#[cfg(not(unix))]
+pub async fn attach() -> Option<(mpsc::UnboundedSender<Action>, mpsc::UnboundedReceiver<Reaction>)> {
+    None
+}
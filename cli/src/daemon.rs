@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Error};
+use tari_launchpad_protocol::launchpad::{Action, LaunchpadAction, Reaction};
+use tari_sdm_launchpad::{
+    daemon::{self, DaemonClient},
+    resources::files::Configurator,
+    LaunchpadBus,
+};
+use tokio::{
+    select,
+    signal::{
+        ctrl_c,
+        unix::{signal, SignalKind},
+    },
+};
+
+use crate::passphrase;
+
+fn socket_path() -> Result<PathBuf, Error> {
+    Ok(Configurator::init()?.socket_path())
+}
+
+/// Attaches to the running daemon or returns `None` if there is no daemon.
+pub async fn try_attach() -> Option<DaemonClient> {
+    let path = socket_path().ok()?;
+    DaemonClient::attach(&path).await.ok()
+}
+
+async fn attach() -> Result<DaemonClient, Error> {
+    try_attach()
+        .await
+        .ok_or_else(|| anyhow!("The launchpad daemon is not running"))
+}
+
+/// Runs the launchpad without the dashboard until the `shutdown` command.
+/// The hangup signal is ignored to keep the containers running when the terminal is closed.
+pub async fn run() -> Result<(), Error> {
+    let path = socket_path()?;
+    if daemon::is_running(&path).await {
+        return Err(anyhow!("The launchpad daemon is already running"));
+    }
+    let passphrase = passphrase::read_passphrase()?;
+    let bus = LaunchpadBus::start()?;
+    if let Some(passphrase) = passphrase {
+        let action = Action::Action(LaunchpadAction::UnlockSecrets(passphrase.into()));
+        bus.incoming.send(action)?;
+    }
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    let server = daemon::serve(path, bus.incoming.clone(), bus.outgoing);
+    tokio::pin!(server);
+    let result = loop {
+        select! {
+            result = &mut server => break result,
+            _ = hangup.recv() => {
+                log::info!("The terminal has been closed. The daemon keeps running.");
+            }
+            _ = terminate.recv() => {
+                bus.incoming.send(Action::Action(LaunchpadAction::Shutdown)).ok();
+                // The server finishes when the bus has stopped
+                break (&mut server).await;
+            }
+            _ = ctrl_c() => {
+                bus.incoming.send(Action::Action(LaunchpadAction::Shutdown)).ok();
+                break (&mut server).await;
+            }
+        }
+    };
+    if result.is_err() {
+        bus.incoming.send(Action::Action(LaunchpadAction::Shutdown)).ok();
+    }
+    if bus.handle.join().is_err() {
+        log::error!("Launchpad worker panicked");
+    }
+    result
+}
+
+/// Prints the statuses of the tasks of the daemon.
+pub async fn status() -> Result<(), Error> {
+    let mut client = attach().await?;
+    match client.outgoing.recv().await {
+        Some(Reaction::State(state)) => {
+            for task_id in &state.tasks_order {
+                if let Some(task) = state.containers.get(task_id) {
+                    println!("{:<16} {}", task_id.to_string(), task.status);
+                }
+            }
+            Ok(())
+        },
+        _ => Err(anyhow!("The daemon hasn't sent the state")),
+    }
+}
+
+/// Stops all the containers and the daemon.
+pub async fn shutdown() -> Result<(), Error> {
+    let mut client = attach().await?;
+    client.incoming.send(Action::Action(LaunchpadAction::Shutdown))?;
+    // The daemon closes the connection when all the tasks are stopped
+    while client.outgoing.recv().await.is_some() {}
+    println!("The launchpad daemon has stopped");
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::io::Stdout;
 
 use anyhow::Error;
+use chrono::{Local, TimeZone};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
@@ -9,7 +10,8 @@ use crossterm::{
 use strum::{Display, EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 use tari_launchpad_protocol::{
     container::TaskState,
-    launchpad::{LaunchpadState, Reaction},
+    launchpad::{ActionKind, LaunchpadAction, LaunchpadState, Reaction},
+    mining::MinerStats,
     session::LaunchpadSession,
 };
 use tari_sdm::ids::{ManagedTask, TaskId};
@@ -20,11 +22,13 @@ use tui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table, Tabs},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Row, Table, Tabs},
     Frame,
     Terminal,
 };
 
+use crate::passphrase::{InputResult, PassphraseInput};
+
 #[derive(Debug, EnumCount, EnumIter, FromRepr, Clone, Copy, Display)]
 pub enum Tab {
     Containers,
@@ -113,6 +117,11 @@ pub struct DashboardState {
     selected_container: Container,
     selected_tab: Tab,
     show_help: bool,
+    passphrase: Option<PassphraseInput>,
+    /// Opens the prompt when the state shows the locked store
+    prompt_requested: bool,
+    /// The last rejected action is shown until the next key
+    failure: Option<String>,
 }
 
 pub struct Dashboard {
@@ -141,6 +150,9 @@ impl Dashboard {
             selected_container: Container::BaseNode,
             selected_tab: Tab::Containers,
             show_help: false,
+            passphrase: None,
+            prompt_requested: false,
+            failure: None,
         };
         Ok(Self {
             terminal,
@@ -155,20 +167,7 @@ impl Dashboard {
     }
 
     pub fn is_alive(&self) -> bool {
-        let has_active_task = self
-            .dashboard_state
-            .state
-            .as_ref()
-            .map(|state| {
-                state
-                    .containers
-                    .values()
-                    .filter(|state| !state.permanent)
-                    .any(|state| state.status.is_active())
-            })
-            .unwrap_or_default();
-
-        !self.terminating || has_active_task
+        !self.terminating
     }
 
     pub fn terminate(&mut self) {
@@ -185,6 +184,40 @@ impl Dashboard {
                     state.apply(delta);
                 }
             },
+            Reaction::ActionFailed { action, reason } => {
+                if action == ActionKind::UnlockSecrets {
+                    // Asks for the passphrase again
+                    self.dashboard_state.prompt_requested = true;
+                }
+                self.dashboard_state.failure = Some(format!("{} failed: {}", action, reason));
+            },
+        }
+        self.check_prompt();
+    }
+
+    /// Asks for the passphrase once the store is known to be locked.
+    pub fn prompt_passphrase(&mut self) {
+        self.dashboard_state.prompt_requested = true;
+        self.check_prompt();
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.dashboard_state.passphrase.is_some()
+    }
+
+    fn check_prompt(&mut self) {
+        let dashboard_state = &mut self.dashboard_state;
+        if let Some(state) = dashboard_state.state.as_ref() {
+            if state.secrets_unlocked {
+                // Another client could unlock the store
+                dashboard_state.passphrase = None;
+                dashboard_state.prompt_requested = false;
+            } else if dashboard_state.prompt_requested {
+                dashboard_state.passphrase = Some(PassphraseInput::new(!state.secrets_exist));
+                dashboard_state.prompt_requested = false;
+            } else {
+                // The prompt is opened by a user or is open already
+            }
         }
     }
 
@@ -192,7 +225,21 @@ impl Dashboard {
         self.events.recv().await
     }
 
-    pub fn process_key(&mut self, key: KeyCode) -> Option<LaunchpadSession> {
+    pub fn process_key(&mut self, key: KeyCode) -> Option<LaunchpadAction> {
+        if let Some(input) = self.dashboard_state.passphrase.as_mut() {
+            match input.process_key(key) {
+                InputResult::Editing => {},
+                InputResult::Cancelled => {
+                    self.dashboard_state.passphrase = None;
+                },
+                InputResult::Entered(passphrase) => {
+                    self.dashboard_state.passphrase = None;
+                    return Some(LaunchpadAction::UnlockSecrets(passphrase.into()));
+                },
+            }
+            return None;
+        }
+        self.dashboard_state.failure = None;
         match key {
             KeyCode::Up | KeyCode::Char('k') => {
                 self.dashboard_state.selected_container.prev();
@@ -213,23 +260,36 @@ impl Dashboard {
                 if let Some(state) = self.state() {
                     let mut session = state.config.session.clone();
                     session.all_active = !session.all_active;
-                    return Some(session);
-                }
-            },
-            KeyCode::Char('q') => {
-                if let Some(state) = self.state() {
-                    let mut session = state.config.session.clone();
-                    session.stop_all();
-                    return Some(session);
+                    return Some(LaunchpadAction::ChangeSession(session));
                 }
             },
             KeyCode::Char(' ') => {
                 if let Some(state) = self.state() {
                     let session = &state.config.session;
                     let container = self.dashboard_state.selected_container;
-                    return Some(toggle_flag(session, container));
+                    let session = toggle_flag(session, container);
+                    return Some(LaunchpadAction::ChangeSession(session));
                 }
             },
+            KeyCode::Char('r') => {
+                let task_id = self.dashboard_state.selected_container.id();
+                return Some(LaunchpadAction::RestartTask(task_id));
+            },
+            KeyCode::Char('x') => {
+                let task_id = self.dashboard_state.selected_container.id();
+                return Some(LaunchpadAction::StopTask(task_id));
+            },
+            KeyCode::Char('p') => {
+                let task_id = self.dashboard_state.selected_container.id();
+                return Some(LaunchpadAction::RepullTask(task_id));
+            },
+            KeyCode::Char('c') => {
+                let task_id = self.dashboard_state.selected_container.id();
+                return Some(LaunchpadAction::RecreateTask(task_id));
+            },
+            KeyCode::Char('u') => {
+                self.prompt_passphrase();
+            },
             _ => {},
         }
         None
@@ -274,6 +334,38 @@ impl<'a, 'b> Render<'a, 'b> {
                 },
             }
         }
+        let dashboard_state = self.dashboard_state;
+        if let Some(input) = dashboard_state.passphrase.as_ref() {
+            self.render_passphrase(rect, input);
+        }
+    }
+
+    fn render_passphrase(&mut self, rect: Rect, input: &PassphraseInput) {
+        let vchunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(rect);
+        let hchunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(20),
+                    Constraint::Percentage(60),
+                    Constraint::Percentage(20),
+                ]
+                .as_ref(),
+            )
+            .split(vchunks[1]);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default().bg(Color::White).fg(Color::Black))
+            .title(Span::styled(
+                input.title(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+        let paragraph = Paragraph::new(input.masked()).block(block);
+        self.f.render_widget(Clear, hchunks[1]);
+        self.f.render_widget(paragraph, hchunks[1]);
     }
 
     fn render_help(&mut self, rect: Rect) {
@@ -283,7 +375,16 @@ impl<'a, 'b> Render<'a, 'b> {
             .constraints([Constraint::Percentage(100)].as_ref())
             .split(rect);
 
-        let text = vec![Spans::from("S - Start/Stop containers")];
+        let text = vec![
+            Spans::from("S - Start/Stop containers"),
+            Spans::from("Space - Toggle the selected container"),
+            Spans::from("R - Restart the selected container"),
+            Spans::from("X - Stop the selected container"),
+            Spans::from("P - Pull the image of the selected container again"),
+            Spans::from("C - Recreate the selected container"),
+            Spans::from("U - Unlock the secret store"),
+            Spans::from("Q - Stop all containers and quit or detach from the daemon"),
+        ];
 
         let block = Block::default()
             .borders(Borders::ALL)
@@ -305,8 +406,21 @@ impl<'a, 'b> Render<'a, 'b> {
         let titles = Tab::iter()
             .map(|s| Spans::from(vec![Span::raw(s.to_string())]))
             .collect();
+        let locked = self
+            .dashboard_state
+            .state
+            .as_ref()
+            .map(|state| !state.secrets_unlocked)
+            .unwrap_or_default();
+        let title = if let Some(failure) = self.dashboard_state.failure.as_ref() {
+            Span::styled(format!("Tabs ({})", failure), Style::default().fg(Color::Red))
+        } else if locked {
+            Span::raw("Tabs (the secret store is locked, press U to unlock)")
+        } else {
+            Span::raw("Tabs")
+        };
         let tabs = Tabs::new(titles)
-            .block(Block::default().borders(Borders::ALL).title("Tabs"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .select(self.dashboard_state.selected_tab as usize)
             .style(Style::default().fg(Color::Cyan))
             .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::Black));
@@ -335,7 +449,11 @@ impl<'a, 'b> Render<'a, 'b> {
                 rows.push(outgoing);
             }
             for wt in &app_state.wallet.transactions {
-                let data = format!("{:?}", wt);
+                let timestamp = Local.from_utc_datetime(&wt.timestamp).format("%Y-%m-%d %H:%M:%S");
+                let data = format!(
+                    "{} #{} {} {} {} {}",
+                    timestamp, wt.tx_id, wt.direction, wt.status, wt.amount, wt.message
+                );
                 let item = ListItem::new(data);
                 logs.push(item);
             }
@@ -356,6 +474,7 @@ impl<'a, 'b> Render<'a, 'b> {
         let mut rows = Vec::new();
         let mut logs = Vec::new();
         let mut selected_container = None;
+        let mut selected_mining = None;
         if let Some(app_state) = self.dashboard_state.state.as_ref() {
             for container in Container::iter() {
                 let id = container.id();
@@ -375,6 +494,11 @@ impl<'a, 'b> Render<'a, 'b> {
                             logs.push(item);
                         }
                         selected_container = Some(state);
+                        selected_mining = match container {
+                            Container::Miner => Some(&app_state.mining.sha3),
+                            Container::XMRig => Some(&app_state.mining.xmrig),
+                            _ => None,
+                        };
                     }
                 } else {
                     let row = Row::new(vec![name, "...".to_string(), "-".to_string()]);
@@ -394,7 +518,7 @@ impl<'a, 'b> Render<'a, 'b> {
             .split(vchunks[0]);
 
         self.render_containers(top_row[0], rows);
-        self.render_stats(top_row[1], selected_container);
+        self.render_stats(top_row[1], selected_container, selected_mining);
         self.render_logs(vchunks[1], logs);
     }
 
@@ -411,7 +535,7 @@ impl<'a, 'b> Render<'a, 'b> {
         self.f.render_widget(table, size);
     }
 
-    fn render_stats(&mut self, size: Rect, state: Option<&TaskState>) {
+    fn render_stats(&mut self, size: Rect, state: Option<&TaskState>, mining: Option<&MinerStats>) {
         let block = Block::default().title("Stats").borders(Borders::ALL);
         if let Some(state) = state {
             let mut rows = Vec::new();
@@ -432,6 +556,20 @@ impl<'a, 'b> Render<'a, 'b> {
                     format!("{:.2} %", stat_data.get_mem_pct()),
                 ]));
             }
+            if let Some(mining) = mining {
+                rows.push(Row::new(vec![
+                    "Hashrate".to_string(),
+                    format!("{:.2} H/s", mining.hashrate),
+                ]));
+                rows.push(Row::new(vec![
+                    "Shares".to_string(),
+                    format!("{} / {}", mining.accepted_shares, mining.rejected_shares),
+                ]));
+                rows.push(Row::new(vec![
+                    "Blocks found".to_string(),
+                    mining.blocks_found.to_string(),
+                ]));
+            }
             let table = Table::new(rows)
                 .block(block)
                 .header(Row::new(vec!["Metric", "Value"]))
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use anyhow::Error;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, TimeZone};
+use clap::{ArgEnum, Args};
+use tari_launchpad_protocol::wallet::ExportFormat;
+use tari_sdm_launchpad::{export, history::TransactionStore, resources::files::Configurator};
+
+#[derive(Debug, Clone, Copy, ArgEnum)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl From<Format> for ExportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Csv => ExportFormat::Csv,
+            Format::Json => ExportFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// The file to write
+    output: PathBuf,
+    #[clap(long, arg_enum, default_value = "csv")]
+    format: Format,
+    /// The first day to export in the local time (YYYY-MM-DD)
+    #[clap(long)]
+    from: Option<NaiveDate>,
+    /// The last day to export in the local time (YYYY-MM-DD)
+    #[clap(long)]
+    to: Option<NaiveDate>,
+}
+
+/// Reads the history directly, so it works
+/// if the launchpad is running or not.
+pub async fn run(args: ExportArgs) -> Result<(), Error> {
+    let configurator = Configurator::init()?;
+    let store = TransactionStore::load(configurator.history_path()).await?;
+    let from = args.from.and_then(start_of_day);
+    let to = args
+        .to
+        .and_then(|date| date.checked_add_signed(Duration::days(1)))
+        .and_then(start_of_day);
+    let summary = export::export_transactions(&store, &args.output, args.format.into(), from, to).await?;
+    println!(
+        "Exported {} transactions over {} days:",
+        summary.transactions, summary.days
+    );
+    for file in summary.files {
+        println!("  {}", file.display());
+    }
+    Ok(())
+}
+
+/// The local midnight in UTC, as the history stores it.
+fn start_of_day(date: NaiveDate) -> Option<NaiveDateTime> {
+    let midnight = date.and_hms_opt(0, 0, 0)?;
+    let local = Local.from_local_datetime(&midnight).earliest()?;
+    Some(local.naive_utc())
+}
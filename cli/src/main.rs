@@ -1,20 +1,63 @@
 mod app;
+#[cfg(unix)]
+mod daemon;
 mod dashboard;
+mod export;
+mod passphrase;
+#[cfg(feature = "remote")]
+mod remote;
 
 use anyhow::Error;
 use app::App;
+use clap::{Parser, Subcommand};
 use dashboard::Dashboard;
+use export::ExportArgs;
+#[cfg(feature = "remote")]
+use remote::RemoteArgs;
+
+#[derive(Parser)]
+#[clap(version, about = "Tari Launchpad (command-line)")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Writes the stored wallet transactions and totals per day to a file.
+    Export(ExportArgs),
+    /// Runs the launchpad in the background. The dashboard
+    /// attaches to it and leaves the containers running on quit.
+    #[cfg(unix)]
+    Daemon,
+    /// Prints the statuses of the containers of the daemon.
+    #[cfg(unix)]
+    Status,
+    /// Stops the containers and the daemon.
+    #[cfg(unix)]
+    Shutdown,
+    /// Runs the launchpad without the dashboard and serves it over WebSocket.
+    #[cfg(feature = "remote")]
+    Remote(RemoteArgs),
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Error> {
-    let mut app = App::init()?;
-    app.routine().await?;
-    Ok(())
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Export(args)) => export::run(args).await,
+        #[cfg(unix)]
+        Some(Command::Daemon) => daemon::run().await,
+        #[cfg(unix)]
+        Some(Command::Status) => daemon::status().await,
+        #[cfg(unix)]
+        Some(Command::Shutdown) => daemon::shutdown().await,
+        #[cfg(feature = "remote")]
+        Some(Command::Remote(args)) => remote::run(args).await,
+        None => {
+            let mut app = App::init().await?;
+            app.routine().await?;
+            Ok(())
+        },
+    }
 }
-
-// Create a cryptographically secure password on length `len`
-// pub fn create_password(len: usize) -> String {
-// use rand::distributions::{Alphanumeric, Distribution};
-// let mut rng = rand::thread_rng();
-// Alphanumeric.sample_iter(&mut rng).take(len).map(char::from).collect()
-// }
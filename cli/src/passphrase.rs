@@ -0,0 +1,144 @@
+use std::io::{self, Write};
+
+use anyhow::{anyhow, Error};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode},
+    tty::IsTty,
+};
+use tari_sdm_launchpad::resources::files::Configurator;
+
+/// The passphrase of the secret store for unattended runs.
+/// The environment of a process is readable by the other processes
+/// of the same user, so the prompt is used if there is a terminal.
+pub const PASSPHRASE_ENV: &str = "TARI_LAUNCHPAD_PASSPHRASE";
+
+pub enum InputResult {
+    Editing,
+    Cancelled,
+    Entered(String),
+}
+
+/// A passphrase typed without an echo.
+/// A new secret store requires the passphrase twice.
+pub struct PassphraseInput {
+    create: bool,
+    first: Option<String>,
+    value: String,
+    mismatch: bool,
+}
+
+impl PassphraseInput {
+    pub fn new(create: bool) -> Self {
+        Self {
+            create,
+            first: None,
+            value: String::new(),
+            mismatch: false,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        if self.mismatch {
+            "The passphrases don't match. Enter a new passphrase"
+        } else if self.first.is_some() {
+            "Repeat the passphrase"
+        } else if self.create {
+            "Enter a new passphrase of the secret store"
+        } else {
+            "Enter the passphrase of the secret store"
+        }
+    }
+
+    pub fn masked(&self) -> String {
+        "*".repeat(self.value.chars().count())
+    }
+
+    pub fn process_key(&mut self, key: KeyCode) -> InputResult {
+        match key {
+            KeyCode::Char(c) => {
+                self.value.push(c);
+            },
+            KeyCode::Backspace => {
+                self.value.pop();
+            },
+            KeyCode::Esc => {
+                return InputResult::Cancelled;
+            },
+            KeyCode::Enter => {
+                return self.submit();
+            },
+            _ => {},
+        }
+        InputResult::Editing
+    }
+
+    fn submit(&mut self) -> InputResult {
+        let value = std::mem::take(&mut self.value);
+        if value.is_empty() {
+            return InputResult::Editing;
+        }
+        if !self.create {
+            return InputResult::Entered(value);
+        }
+        match self.first.take() {
+            None => {
+                self.first = Some(value);
+                self.mismatch = false;
+                InputResult::Editing
+            },
+            Some(first) if first == value => InputResult::Entered(value),
+            Some(_) => {
+                self.mismatch = true;
+                InputResult::Editing
+            },
+        }
+    }
+}
+
+/// Takes the passphrase from the environment or asks for it in the terminal.
+/// Returns `None` without a terminal, then an attached dashboard unlocks the store.
+pub fn read_passphrase() -> Result<Option<String>, Error> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        return Ok(Some(passphrase));
+    }
+    if !io::stdin().is_tty() {
+        return Ok(None);
+    }
+    let create = !Configurator::init()?.secrets_path().exists();
+    let mut input = PassphraseInput::new(create);
+    enable_raw_mode()?;
+    let result = prompt(&mut input);
+    disable_raw_mode()?;
+    result
+}
+
+fn prompt(input: &mut PassphraseInput) -> Result<Option<String>, Error> {
+    let mut stdout = io::stdout();
+    loop {
+        write!(stdout, "{}: ", input.title())?;
+        stdout.flush()?;
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                    write!(stdout, "\r\n")?;
+                    return Err(anyhow!("The passphrase prompt was interrupted"));
+                }
+                let result = input.process_key(key.code);
+                if key.code == KeyCode::Enter {
+                    // The raw mode doesn't move the cursor to the next line
+                    write!(stdout, "\r\n")?;
+                }
+                match result {
+                    InputResult::Editing if key.code == KeyCode::Enter => break,
+                    InputResult::Editing => {},
+                    InputResult::Cancelled => {
+                        write!(stdout, "\r\n")?;
+                        return Ok(None);
+                    },
+                    InputResult::Entered(passphrase) => return Ok(Some(passphrase)),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::net::SocketAddr;
+
+use anyhow::Error;
+use clap::Args;
+use tari_launchpad_protocol::launchpad::{Action, LaunchpadAction};
+use tari_sdm_launchpad::{
+    remote::{self, RemoteConfig},
+    secrets,
+    LaunchpadBus,
+};
+use tokio::{select, signal};
+
+use crate::{app, passphrase};
+
+/// The token of the remote clients.
+const TOKEN_ENV: &str = "TARI_LAUNCHPAD_REMOTE_TOKEN";
+
+#[derive(Debug, Args)]
+pub struct RemoteArgs {
+    /// The address to listen (127.0.0.1:18500 by default)
+    #[clap(long)]
+    address: Option<SocketAddr>,
+}
+
+/// Runs the launchpad without the dashboard until it's stopped
+/// by a remote client or interrupted. Serves the daemon instead if it's running,
+/// since two buses would control the same containers.
+pub async fn run(args: RemoteArgs) -> Result<(), Error> {
+    let token = match std::env::var(TOKEN_ENV) {
+        Ok(token) if !token.is_empty() => token.into(),
+        _ => {
+            let token = secrets::create_password(32);
+            println!("The token of the remote clients: {}", token.reveal());
+            token
+        },
+    };
+    let mut config = RemoteConfig::new(token);
+    if let Some(address) = args.address {
+        config.address = address;
+    }
+    if let Some((incoming, outgoing)) = app::attach().await {
+        println!("Serving the running launchpad daemon");
+        // The daemon keeps the containers running when the server is interrupted
+        return select! {
+            result = remote::serve(config, incoming, outgoing) => result,
+            _ = signal::ctrl_c() => Ok(()),
+        };
+    }
+    let passphrase = passphrase::read_passphrase()?;
+    let bus = LaunchpadBus::start()?;
+    if let Some(passphrase) = passphrase {
+        let action = Action::Action(LaunchpadAction::UnlockSecrets(passphrase.into()));
+        bus.incoming.send(action)?;
+    }
+    let server = remote::serve(config, bus.incoming.clone(), bus.outgoing);
+    tokio::pin!(server);
+    let result = select! {
+        result = &mut server => result,
+        _ = signal::ctrl_c() => {
+            bus.incoming.send(Action::Action(LaunchpadAction::Shutdown)).ok();
+            // The server finishes when the bus has stopped
+            server.await
+        }
+    };
+    if result.is_err() {
+        bus.incoming.send(Action::Action(LaunchpadAction::Shutdown)).ok();
+    }
+    if bus.handle.join().is_err() {
+        log::error!("Launchpad worker panicked");
+    }
+    result
+}
@@ -5,6 +5,8 @@
 # - APP_NAME - the name of the app to run. This var is used to set the location of log files, and app-specific config
 # - APP_EXEC - the name of the application executable. Just the name is enough, since the Dockerfile will put it in /usr/bin
 # - WAIT_FOR_TOR - set to the delay in seconds to pause at the beginning of this script.
+# - RECOVERY_SEED_WORDS_FILE - optional file with the seed words to recover a wallet from. It's passed to the
+#   seed words prompt of the wallet on stdin.
 #
 
 APP_NAME=${APP_NAME:-base_node}
@@ -36,4 +38,12 @@ cd "${TARI_BASE}" || exit 1
 
 echo "Starting ${APP_NAME} ..."
 echo " > command: ${APP_EXEC} --config ${TARI_CONFIG} --base-path ${TARI_BASE} ${@} <"
-exec ${APP_EXEC} --config ${TARI_CONFIG} --base-path ${TARI_BASE} ${@} || exit 1
+
+# The seed words of a recovery are not passed as arguments, since the arguments
+# of a process can be read by any user of the host. The wallet prompts for them
+# without the --seed-words argument, so they are sent to stdin instead.
+if [[ -n "${RECOVERY_SEED_WORDS_FILE}" ]]; then
+  exec ${APP_EXEC} --config ${TARI_CONFIG} --base-path ${TARI_BASE} "$@" < "${RECOVERY_SEED_WORDS_FILE}" || exit 1
+fi
+
+exec ${APP_EXEC} --config ${TARI_CONFIG} --base-path ${TARI_BASE} "$@" || exit 1
@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{session::LaunchpadSession, settings::LaunchpadSettings};
+use crate::{session::LaunchpadSession, settings::LaunchpadSettings, wallet::WalletRestore};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LaunchpadConfig {
     pub session: LaunchpadSession,
     pub settings: Option<LaunchpadSettings>,
+    /// Starts the wallet in the recovery mode until it's ready.
+    #[serde(skip)]
+    pub restore: Option<WalletRestore>,
 }
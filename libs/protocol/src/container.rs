@@ -53,6 +53,10 @@ pub struct TaskState {
     pub tail: VecDeque<String>,
     pub stats: TaskStats,
     pub permanent: bool,
+    /// The last unexpected exit of the task.
+    pub last_exit: Option<ExitInfo>,
+    /// How many times the task exited unexpectedly.
+    pub exits: u32,
 }
 
 impl TaskState {
@@ -62,6 +66,8 @@ impl TaskState {
             tail: VecDeque::with_capacity(TAIL_LIMIT),
             stats: TaskStats::new(),
             permanent,
+            last_exit: None,
+            exits: 0,
         }
     }
 
@@ -79,6 +85,10 @@ impl TaskState {
             TaskDelta::StatsRecord(record) => {
                 self.stats.push(record);
             },
+            TaskDelta::Exited(info) => {
+                self.exits += 1;
+                self.last_exit = Some(info);
+            },
         }
     }
 }
@@ -105,7 +115,16 @@ pub enum TaskStatus {
     Pending,
     Progress(TaskProgress),
     Active,
-    // TODO: Add failed with a reason?
+    /// The task failed. It will be restarted at `next_retry`
+    /// or never if the value is not set.
+    Failed {
+        reason: String,
+        attempts: u32,
+        next_retry: Option<NaiveDateTime>,
+    },
+    /// The task is disabled by the settings
+    /// and its resources are not created.
+    Unavailable,
 }
 
 impl TaskStatus {
@@ -113,13 +132,21 @@ impl TaskStatus {
         matches!(self, Self::Active)
     }
 
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed { .. })
+    }
+
     pub fn is_active(&self) -> bool {
-        !matches!(self, Self::Inactive)
+        !matches!(self, Self::Inactive | Self::Unavailable)
     }
 
     pub fn is_inactive(&self) -> bool {
         matches!(self, Self::Inactive)
     }
+
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, Self::Unavailable)
+    }
 }
 
 impl fmt::Display for TaskStatus {
@@ -129,6 +156,8 @@ impl fmt::Display for TaskStatus {
             Self::Pending => write!(f, "Pending"),
             Self::Progress(value) => write!(f, "Progress({} - {}%)", value.stage, value.pct),
             Self::Active => write!(f, "Active"),
+            Self::Failed { reason, attempts, .. } => write!(f, "Failed({} - attempts: {})", reason, attempts),
+            Self::Unavailable => write!(f, "Unavailable"),
         }
     }
 }
@@ -138,6 +167,33 @@ pub enum TaskDelta {
     UpdateStatus(TaskStatus),
     LogRecord(String),
     StatsRecord(StatsData),
+    Exited(ExitInfo),
+}
+
+/// Details about a task that stopped by itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitInfo {
+    pub timestamp: NaiveDateTime,
+    pub exit_code: Option<i64>,
+    pub oom_killed: bool,
+}
+
+impl ExitInfo {
+    pub fn is_success(&self) -> bool {
+        !self.oom_killed && self.exit_code == Some(0)
+    }
+}
+
+impl fmt::Display for ExitInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.oom_killed {
+            write!(f, "Out of memory")
+        } else if let Some(code) = self.exit_code {
+            write!(f, "Exited with code {}", code)
+        } else {
+            write!(f, "Exited")
+        }
+    }
 }
 
 // TODO: Add own `Frame` type and use if for
@@ -24,13 +24,16 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumDiscriminants};
+use tari_utilities::Hidden;
 
 use crate::{
     config::LaunchpadConfig,
     container::{TaskDelta, TaskId, TaskState},
+    mining::{MiningDelta, MiningState},
     session::LaunchpadSession,
-    settings::LaunchpadSettings,
-    wallet::{WalletDelta, WalletState},
+    settings::{LaunchpadSettings, SettingsPatch},
+    wallet::{ExportTransactions, TransactionQuery, WalletAction, WalletDelta, WalletRestore, WalletState},
 };
 
 /// An action sent from UI to the backend.
@@ -39,26 +42,74 @@ pub enum Action {
     Action(LaunchpadAction),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, EnumDiscriminants)]
+#[strum_discriminants(name(ActionKind), derive(Serialize, Deserialize, Display))]
 pub enum LaunchpadAction {
     Connect,
     ChangeSession(LaunchpadSession),
+    UpdateSettings(SettingsPatch),
+    /// Stops all the tasks gracefully and closes the bus.
+    Shutdown,
+    /// Restarts a task or starts a stopped one.
+    RestartTask(TaskId),
+    /// Stops a task until it will be restarted.
+    StopTask(TaskId),
+    /// Pulls the image of a task again and recreates the container.
+    RepullTask(TaskId),
+    /// Removes the container of a task and creates it again.
+    RecreateTask(TaskId),
+    /// Requires the active wallet.
+    WalletAction(WalletAction),
+    /// Reads a page of the stored transactions.
+    /// It works if the wallet is not running.
+    QueryTransactions(TransactionQuery),
+    ExportTransactions(ExportTransactions),
+    /// Sends the seed words of a new wallet once.
+    RevealSeedWords,
+    /// Deletes the seed words file after the words have been written down.
+    ConfirmSeedWordsBackup,
+    /// Requires the stopped wallet.
+    RestoreWallet(WalletRestore),
+    /// Decrypts the secret store with the passphrase.
+    /// The store is created with the passphrase if it doesn't exist.
+    /// The tasks that require passwords wait for it.
+    UnlockSecrets(Hidden<String>),
+    /// Generates a new password of the Tor control port.
+    /// Requires the unlocked secret store.
+    RotateTorPassword,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LaunchpadDelta {
     UpdateConfig(LaunchpadSettings),
     UpdateSession(LaunchpadSession),
-    TaskAdded { id: TaskId, state: TaskState },
-    TaskDelta { id: TaskId, delta: TaskDelta },
+    TaskAdded {
+        id: TaskId,
+        state: TaskState,
+    },
+    TaskDelta {
+        id: TaskId,
+        delta: TaskDelta,
+    },
+    /// Tasks ordered by dependencies
+    TasksOrder(Vec<TaskId>),
     WalletDelta(WalletDelta),
+    MiningDelta(MiningDelta),
+    SecretsUnlocked,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct LaunchpadState {
     pub config: LaunchpadConfig,
     pub containers: HashMap<TaskId, TaskState>,
+    /// Every task follows its dependencies here.
+    pub tasks_order: Vec<TaskId>,
     pub wallet: WalletState,
+    pub mining: MiningState,
+    /// The secret store is unlocked in the current session.
+    pub secrets_unlocked: bool,
+    /// The secret store was created before, so the passphrase is not a new one.
+    pub secrets_exist: bool,
 }
 
 impl LaunchpadState {
@@ -66,7 +117,11 @@ impl LaunchpadState {
         Self {
             config: LaunchpadConfig::default(),
             containers: HashMap::new(),
+            tasks_order: Vec::new(),
             wallet: WalletState::default(),
+            mining: MiningState::default(),
+            secrets_unlocked: false,
+            secrets_exist: false,
         }
     }
 }
@@ -75,6 +130,12 @@ impl LaunchpadState {
 pub enum Reaction {
     State(LaunchpadState),
     Delta(LaunchpadDelta),
+    /// The action was rejected. Only the kind of the action
+    /// is sent back, since the action could carry secrets.
+    ActionFailed {
+        action: ActionKind,
+        reason: String,
+    },
 }
 
 impl LaunchpadState {
@@ -96,9 +157,19 @@ impl LaunchpadState {
                     state.apply(delta);
                 }
             },
+            TasksOrder(order) => {
+                self.tasks_order = order;
+            },
             WalletDelta(delta) => {
                 self.wallet.apply(delta);
             },
+            MiningDelta(delta) => {
+                self.mining.apply(delta);
+            },
+            SecretsUnlocked => {
+                self.secrets_unlocked = true;
+                self.secrets_exist = true;
+            },
         }
     }
 }
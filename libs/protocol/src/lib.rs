@@ -25,6 +25,7 @@ pub mod config;
 pub mod container;
 pub mod images;
 pub mod launchpad;
+pub mod mining;
 pub mod session;
 pub mod settings;
 pub mod wallet;
@@ -0,0 +1,74 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use serde::{Deserialize, Serialize};
+
+/// Telemetry of the miners parsed from their logs.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct MiningState {
+    pub sha3: MinerStats,
+    pub xmrig: MinerStats,
+}
+
+impl MiningState {
+    pub fn stats_mut(&mut self, miner: MinerKind) -> &mut MinerStats {
+        match miner {
+            MinerKind::Sha3 => &mut self.sha3,
+            MinerKind::Xmrig => &mut self.xmrig,
+        }
+    }
+
+    pub fn apply(&mut self, delta: MiningDelta) {
+        match delta {
+            MiningDelta::UpdateStats { miner, stats } => {
+                *self.stats_mut(miner) = stats;
+            },
+            MiningDelta::Stopped(miner) => {
+                self.stats_mut(miner).hashrate = 0.0;
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MinerKind {
+    Sha3,
+    Xmrig,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MinerStats {
+    /// The total hashrate in hashes per second
+    pub hashrate: f64,
+    pub accepted_shares: u64,
+    pub rejected_shares: u64,
+    pub blocks_found: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MiningDelta {
+    /// Replaces the stats of a running miner.
+    UpdateStats { miner: MinerKind, stats: MinerStats },
+    /// The miner doesn't hash anymore, but the counters are kept.
+    Stopped(MinerKind),
+}
@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tari_utilities::Hidden;
@@ -34,35 +34,115 @@ http://xmr-lux.boldsuck.org:38081,\
 http://singapore.node.xmr.pm:38081";
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct BaseNodeConfig {}
 
+/// The name of the secret with the password of the wallet database.
+pub const WALLET_PASSWORD: &str = "wallet_password";
+
+/// The name of the secret with the password of the Tor control port.
+pub const TOR_CONTROL_PASSWORD: &str = "tor_control_password";
+
+/// The name of the secret with the password of the monero daemon.
+pub const MONERO_PASSWORD: &str = "monero_password";
+
+/// A reference to a value of the encrypted secret store.
+///
+/// Only the name is persisted with the settings. The value is filled
+/// by the launchpad when the store is unlocked and it's never serialized.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Secret {
+    pub name: String,
+    #[serde(skip)]
+    pub value: Option<Hidden<String>>,
+}
+
+impl Secret {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: None,
+        }
+    }
+
+    /// Returns the value if the secret store is unlocked.
+    pub fn reveal(&self) -> Option<&str> {
+        self.value.as_ref().map(|value| value.reveal().as_str())
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WalletConfig {
     /// The password to de/en-crypt the wallet database
-    pub password: Hidden<String>,
+    pub password: Secret,
+}
+
+impl Default for WalletConfig {
+    fn default() -> Self {
+        Self {
+            password: Secret::new(WALLET_PASSWORD),
+        }
+    }
 }
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct XmRigConfig {
     /// The address that will accept Monero mining rewards
     pub monero_mining_address: String,
 }
 
-#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Sha3MinerConfig {
-    /// The number of threads to employ for SHA3 mining
+    /// The number of threads to employ for SHA3 mining. Zero means auto.
     pub num_mining_threads: usize,
+    /// Mine only if the base node is synced to the tip
+    pub mine_on_tip_only: bool,
+    /// Extra data added to the coinbase transaction
+    pub coinbase_extra: String,
+}
+
+impl Default for Sha3MinerConfig {
+    fn default() -> Self {
+        Self {
+            num_mining_threads: 0,
+            mine_on_tip_only: true,
+            coinbase_extra: String::new(),
+        }
+    }
+}
+
+impl Sha3MinerConfig {
+    /// The number of threads. Zero means the default value.
+    pub fn mining_threads(&self) -> usize {
+        if self.num_mining_threads == 0 {
+            default_mining_threads()
+        } else {
+            self.num_mining_threads
+        }
+    }
+}
+
+/// Leaves one CPU of the host for other services.
+pub fn default_mining_threads() -> usize {
+    let cpus = std::thread::available_parallelism().map(usize::from).unwrap_or(1);
+    cpus.saturating_sub(1).max(1)
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct MmProxyConfig {
     /// A URL specifying the Monero daemon to connect to
     pub monerod_url: String,
     /// If required, the monero username for the monero daemon
     pub monero_username: String,
     /// If required, the password needed to access the monero deamon
-    // #[serde(skip_serializing)]
-    pub monero_password: Hidden<String>,
+    pub monero_password: Secret,
     /// If true, provide the monero username and password to the daemon. Otherwise those strings are ignored.
     pub monero_use_auth: bool,
 }
@@ -72,7 +152,7 @@ impl Default for MmProxyConfig {
         MmProxyConfig {
             monerod_url: DEFAULT_MONEROD_URL.to_string(),
             monero_username: String::new(),
-            monero_password: Hidden::from(String::new()),
+            monero_password: Secret::new(MONERO_PASSWORD),
             monero_use_auth: false,
         }
     }
@@ -88,18 +168,37 @@ impl MmProxyConfig {
     }
 }
 
+/// Overrides of the registry and the tag for a single image.
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ImageSettings {
+    pub registry: Option<String>,
+    /// A tag or a digest (`sha256:...`) to pin the image
+    pub tag: Option<String>,
+}
+
+impl ImageSettings {
+    pub fn is_empty(&self) -> bool {
+        self.registry.is_none() && self.tag.is_none()
+    }
+}
+
 /// Tari Launchpad configuration struct. This will generally
 /// be populated from some front-end or persistent storage
 /// file and is used to generate the environment variables
 /// needed to configure and run the various docker containers.
+///
+/// Missing fields are filled with defaults on deserialization
+/// to keep the persisted settings compatible with new releases.
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct LaunchpadSettings {
     /// The directory to use for config, id files and logs
     pub data_directory: PathBuf,
     /// The Tari network to use. Default = esmeralda
     pub tari_network: TariNetwork,
     /// The tor control password to share among containers.
-    pub tor_control_password: Hidden<String>,
+    pub tor_control_password: Secret,
     /// Whether to spin up a base node or not, with
     /// the given configuration. Usually you want this.
     pub base_node: Option<BaseNodeConfig>,
@@ -123,11 +222,23 @@ pub struct LaunchpadSettings {
     /// The Docker registry to use to download images.
     /// By default we use quay.io
     pub registry: Option<String>,
-    /// The docker tag to use. By default, we use 'latest'
+    /// The docker tag to use. By default, we use 'latest'.
+    /// A digest (`sha256:...`) pins the exact images.
     pub tag: Option<String>,
+    /// Overrides of the `registry` and the `tag` by the name of the image.
+    /// Unlike the global values, they are applied to third-party images too.
+    pub images: BTreeMap<String, ImageSettings>,
 
     pub with_monitoring: bool,
     pub with_tor: bool,
+    /// The public IP or the domain name of the host. It's used
+    /// by the base node and the wallet to listen TCP connections
+    /// from other nodes when Tor is disabled.
+    pub public_address: Option<String>,
+    /// Runs the Tari applications as processes of the host instead of
+    /// containers. The binaries are searched in `PATH`.
+    /// It's applied on the next start of the launchpad.
+    pub native_processes: bool,
 }
 
 impl Default for LaunchpadSettings {
@@ -135,7 +246,7 @@ impl Default for LaunchpadSettings {
         Self {
             data_directory: PathBuf::default(),
             tari_network: TariNetwork::Esmeralda,
-            tor_control_password: Hidden::from(String::new()),
+            tor_control_password: Secret::new(TOR_CONTROL_PASSWORD),
             base_node: None,
             wallet: None,
             sha3_miner: None,
@@ -143,10 +254,247 @@ impl Default for LaunchpadSettings {
             xmrig: None,
             registry: None,
             tag: None,
+            images: BTreeMap::new(),
             with_monitoring: true,
             with_tor: true,
+            public_address: None,
+            native_processes: false,
+        }
+    }
+}
+
+/// The maximal number of threads that could be used by the SHA3 miner.
+pub const MAX_MINING_THREADS: usize = 1024;
+
+/// The maximal length of the coinbase extra in bytes.
+pub const MAX_COINBASE_EXTRA: usize = 64;
+
+/// A partial update of the `LaunchpadSettings`.
+/// Only the fields that are set will be changed.
+/// An empty string resets the `registry`, the `tag`, the `public_address`
+/// or the `monero_mining_address` to the default value, zero `num_mining_threads`
+/// resets the number of threads to auto.
+/// Overrides of `images` are merged by the name and an empty override removes the entry.
+#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SettingsPatch {
+    pub tari_network: Option<TariNetwork>,
+    pub num_mining_threads: Option<usize>,
+    pub mine_on_tip_only: Option<bool>,
+    pub coinbase_extra: Option<String>,
+    pub monero_mining_address: Option<String>,
+    pub monerod_url: Option<String>,
+    pub monero_username: Option<String>,
+    pub monero_password: Option<Hidden<String>>,
+    pub monero_use_auth: Option<bool>,
+    pub registry: Option<String>,
+    pub tag: Option<String>,
+    pub images: Option<BTreeMap<String, ImageSettings>>,
+    pub with_monitoring: Option<bool>,
+    pub with_tor: Option<bool>,
+    pub public_address: Option<String>,
+    pub native_processes: Option<bool>,
+}
+
+#[derive(Debug, Error)]
+pub enum SettingsError {
+    #[error("Invalid number of mining threads {0} (expected at most {})", MAX_MINING_THREADS)]
+    InvalidMiningThreads(usize),
+    #[error("The coinbase extra is longer than {} bytes", MAX_COINBASE_EXTRA)]
+    InvalidCoinbaseExtra,
+    #[error("Invalid monero address: {0}")]
+    InvalidMoneroAddress(String),
+    #[error("Invalid monero daemon url: {0}")]
+    InvalidMonerodUrl(String),
+    #[error("Invalid value of the {field}: {value}")]
+    InvalidValue { field: &'static str, value: String },
+}
+
+impl SettingsPatch {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(threads) = self.num_mining_threads {
+            if threads > MAX_MINING_THREADS {
+                return Err(SettingsError::InvalidMiningThreads(threads));
+            }
+        }
+        if let Some(extra) = self.coinbase_extra.as_ref() {
+            if extra.len() > MAX_COINBASE_EXTRA {
+                return Err(SettingsError::InvalidCoinbaseExtra);
+            }
+        }
+        if let Some(address) = self.monero_mining_address.as_ref().filter(|value| !value.is_empty()) {
+            validate_monero_address(address)?;
+        }
+        if let Some(urls) = self.monerod_url.as_ref() {
+            validate_monerod_urls(urls)?;
+        }
+        if let Some(registry) = self.registry.as_ref() {
+            validate_no_whitespace("registry", registry)?;
+        }
+        if let Some(tag) = self.tag.as_ref() {
+            validate_tag(tag)?;
+        }
+        if let Some(address) = self.public_address.as_ref() {
+            validate_no_whitespace("public_address", address)?;
+        }
+        for image in self.images.iter().flat_map(BTreeMap::values) {
+            if let Some(registry) = image.registry.as_ref() {
+                validate_no_whitespace("registry", registry)?;
+            }
+            if let Some(tag) = image.tag.as_ref() {
+                validate_tag(tag)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl LaunchpadSettings {
+    /// Validates the patch and applies it to the settings.
+    /// The settings stay unchanged if the patch is not valid.
+    pub fn apply_patch(&mut self, patch: SettingsPatch) -> Result<(), SettingsError> {
+        patch.validate()?;
+        if let Some(network) = patch.tari_network {
+            self.tari_network = network;
+        }
+        let has_sha3_miner_changes =
+            patch.num_mining_threads.is_some() || patch.mine_on_tip_only.is_some() || patch.coinbase_extra.is_some();
+        if has_sha3_miner_changes {
+            let sha3_miner = self.sha3_miner.get_or_insert_with(Default::default);
+            if let Some(threads) = patch.num_mining_threads {
+                sha3_miner.num_mining_threads = threads;
+            }
+            if let Some(flag) = patch.mine_on_tip_only {
+                sha3_miner.mine_on_tip_only = flag;
+            }
+            if let Some(extra) = patch.coinbase_extra {
+                sha3_miner.coinbase_extra = extra;
+            }
+        }
+        if let Some(address) = patch.monero_mining_address {
+            if address.is_empty() {
+                if let Some(xmrig) = self.xmrig.as_mut() {
+                    xmrig.monero_mining_address.clear();
+                }
+            } else {
+                self.xmrig.get_or_insert_with(Default::default).monero_mining_address = address;
+            }
+        }
+        let has_mm_proxy_changes = patch.monerod_url.is_some() ||
+            patch.monero_username.is_some() ||
+            patch.monero_password.is_some() ||
+            patch.monero_use_auth.is_some();
+        if has_mm_proxy_changes {
+            let mm_proxy = self.mm_proxy.get_or_insert_with(Default::default);
+            if let Some(url) = patch.monerod_url {
+                mm_proxy.monerod_url = url;
+            }
+            if let Some(username) = patch.monero_username {
+                mm_proxy.monero_username = username;
+            }
+            if let Some(password) = patch.monero_password {
+                mm_proxy.monero_password.value = Some(password);
+            }
+            if let Some(use_auth) = patch.monero_use_auth {
+                mm_proxy.monero_use_auth = use_auth;
+            }
+        }
+        if let Some(registry) = patch.registry {
+            self.registry = Some(registry).filter(|value| !value.is_empty());
+        }
+        if let Some(tag) = patch.tag {
+            self.tag = Some(tag).filter(|value| !value.is_empty());
+        }
+        for (name, image) in patch.images.into_iter().flatten() {
+            let image = ImageSettings {
+                registry: image.registry.filter(|value| !value.is_empty()),
+                tag: image.tag.filter(|value| !value.is_empty()),
+            };
+            if image.is_empty() {
+                self.images.remove(&name);
+            } else {
+                self.images.insert(name, image);
+            }
+        }
+        if let Some(flag) = patch.with_monitoring {
+            self.with_monitoring = flag;
+        }
+        if let Some(flag) = patch.with_tor {
+            self.with_tor = flag;
+        }
+        if let Some(address) = patch.public_address {
+            self.public_address = Some(address).filter(|value| !value.is_empty());
+        }
+        if let Some(flag) = patch.native_processes {
+            self.native_processes = flag;
+        }
+        Ok(())
+    }
+
+    /// Returns all the secrets referenced by the settings.
+    pub fn secrets_mut(&mut self) -> Vec<&mut Secret> {
+        let mut secrets = vec![&mut self.tor_control_password];
+        if let Some(wallet) = self.wallet.as_mut() {
+            secrets.push(&mut wallet.password);
+        }
+        if let Some(mm_proxy) = self.mm_proxy.as_mut() {
+            secrets.push(&mut mm_proxy.monero_password);
+        }
+        secrets
+    }
+}
+
+/// Checks the standard (95 chars) or the integrated (106 chars)
+/// monero address that uses the base58 alphabet.
+fn validate_monero_address(address: &str) -> Result<(), SettingsError> {
+    let is_base58 = address
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'));
+    if is_base58 && matches!(address.len(), 95 | 106) {
+        Ok(())
+    } else {
+        Err(SettingsError::InvalidMoneroAddress(address.to_owned()))
+    }
+}
+
+/// Checks a comma-separated list of the monero daemon urls.
+fn validate_monerod_urls(urls: &str) -> Result<(), SettingsError> {
+    for url in urls.split(',') {
+        let url = url.trim();
+        let host = url
+            .strip_prefix("http://")
+            .or_else(|| url.strip_prefix("https://"))
+            .unwrap_or_default();
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            return Err(SettingsError::InvalidMonerodUrl(url.to_owned()));
+        }
+    }
+    Ok(())
+}
+
+fn validate_no_whitespace(field: &'static str, value: &str) -> Result<(), SettingsError> {
+    if value.contains(char::is_whitespace) {
+        Err(SettingsError::InvalidValue {
+            field,
+            value: value.to_owned(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks a tag or a digest in the `sha256:<64 hex digits>` format.
+fn validate_tag(tag: &str) -> Result<(), SettingsError> {
+    validate_no_whitespace("tag", tag)?;
+    if let Some((_, digest)) = tag.split_once("sha256:") {
+        if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(SettingsError::InvalidValue {
+                field: "tag",
+                value: tag.to_owned(),
+            });
         }
     }
+    Ok(())
 }
 
 #[derive(Debug, Error)]
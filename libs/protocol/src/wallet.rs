@@ -21,17 +21,34 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, path::PathBuf};
 
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use tari_utilities::Hidden;
 
 const HISTORY_LIMIT: usize = 30;
 
+/// The size of a page of the stored transactions if the limit is not set.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// The number of words in the seed of a wallet.
+pub const SEED_WORDS_COUNT: usize = 24;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletState {
     pub active: bool,
     pub balance: Option<WalletBalance>,
     pub transactions: VecDeque<WalletTransaction>,
+    /// Results of the recent transfers.
+    pub payments: VecDeque<PaymentResult>,
+    /// The last requested page of the stored transactions.
+    pub history: Option<TransactionsPage>,
+    pub last_export: Option<ExportSummary>,
+    pub backup: SeedWordsBackup,
+    /// Revealed seed words. They are never sent within a whole state.
+    #[serde(skip)]
+    pub seed_words: Option<Hidden<Vec<String>>>,
 }
 
 impl Default for WalletState {
@@ -40,6 +57,11 @@ impl Default for WalletState {
             active: false,
             balance: None,
             transactions: VecDeque::with_capacity(HISTORY_LIMIT),
+            payments: VecDeque::with_capacity(HISTORY_LIMIT),
+            history: None,
+            last_export: None,
+            backup: SeedWordsBackup::NotFound,
+            seed_words: None,
         }
     }
 }
@@ -69,6 +91,27 @@ impl WalletState {
                 }
                 self.transactions.push_front(trans);
             },
+            WalletDelta::LogPayment(payment) => {
+                if self.payments.len() >= HISTORY_LIMIT {
+                    self.payments.pop_back();
+                }
+                self.payments.push_front(payment);
+            },
+            WalletDelta::UpdateHistory(page) => {
+                self.history = Some(page);
+            },
+            WalletDelta::Exported(summary) => {
+                self.last_export = Some(summary);
+            },
+            WalletDelta::UpdateBackup(backup) => {
+                self.backup = backup;
+                if backup != SeedWordsBackup::Revealed {
+                    self.seed_words.take();
+                }
+            },
+            WalletDelta::SeedWords(words) => {
+                self.seed_words = Some(words);
+            },
         }
     }
 }
@@ -78,9 +121,39 @@ pub enum WalletDelta {
     SetActive(bool),
     UpdateBalance(WalletBalance),
     LogTransaction(WalletTransaction),
+    /// A result of the transfer to a single recipient.
+    LogPayment(PaymentResult),
+    UpdateHistory(TransactionsPage),
+    Exported(ExportSummary),
+    UpdateBackup(SeedWordsBackup),
+    /// Sent only once to the client that asked to reveal them.
+    SeedWords(Hidden<Vec<String>>),
 }
 
+/// A stage of the backup of the seed words created by a new wallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SeedWordsBackup {
+    /// The wallet has no seed words file yet or it was deleted.
+    NotFound,
+    /// The seed words file exists and the words can be revealed.
+    Pending,
+    /// The words were revealed and wait for the confirmation of the backup.
+    Revealed,
+    /// The backup was confirmed and the file was deleted.
+    Done,
+}
+
+/// Restores a wallet from the seed words of a backup.
+/// The current wallet is moved aside and the wallet starts in the recovery mode.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletRestore {
+    pub seed_words: Hidden<Vec<String>>,
+    /// Days since the Unix epoch when the wallet was created.
+    /// The recovery doesn't scan the blocks mined before it.
+    pub birthday: Option<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WalletTransaction {
     pub event: String,
     pub tx_id: String,
@@ -89,11 +162,124 @@ pub struct WalletTransaction {
     pub status: String,
     pub direction: String,
     pub amount: u64,
+    pub fee: u64,
     pub message: String,
     pub is_coinbase: bool,
+    /// UTC
+    pub timestamp: NaiveDateTime,
+}
+
+/// Filters and a page of the stored transactions.
+/// The transactions are ordered from the newest to the oldest.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransactionQuery {
+    pub direction: Option<String>,
+    pub status: Option<String>,
+    pub is_coinbase: Option<bool>,
+    /// Inclusive, UTC
+    pub from: Option<NaiveDateTime>,
+    /// Exclusive, UTC
+    pub to: Option<NaiveDateTime>,
+    pub offset: usize,
+    /// Zero means `DEFAULT_PAGE_SIZE`
+    pub limit: usize,
+}
+
+impl TransactionQuery {
+    pub fn page_size(&self) -> usize {
+        if self.limit == 0 {
+            DEFAULT_PAGE_SIZE
+        } else {
+            self.limit
+        }
+    }
+
+    pub fn matches(&self, transaction: &WalletTransaction) -> bool {
+        let same = |filter: &Option<String>, value: &str| {
+            filter
+                .as_ref()
+                .map(|filter| normalize(filter) == normalize(value))
+                .unwrap_or(true)
+        };
+        same(&self.direction, &transaction.direction) &&
+            same(&self.status, &transaction.status) &&
+            self.is_coinbase
+                .map(|flag| flag == transaction.is_coinbase)
+                .unwrap_or(true) &&
+            self.from.map(|from| transaction.timestamp >= from).unwrap_or(true) &&
+            self.to.map(|to| transaction.timestamp < to).unwrap_or(true)
+    }
+}
+
+/// The wallet and the grpc use different spelling
+/// of statuses: `Mined Confirmed` vs `MinedConfirmed`.
+pub fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionsPage {
+    pub query: TransactionQuery,
+    /// The number of transactions that match the filters
+    pub total: usize,
+    pub transactions: Vec<WalletTransaction>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum WalletAction {
-    TransferFunds,
+    TransferFunds(TransferFunds),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferFunds {
+    pub payments: Vec<Payment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    /// A public key in hex or an emoji id of the recipient
+    pub address: String,
+    pub amount: u64,
+    pub fee_per_gram: u64,
+    pub message: String,
+    /// Sends the funds without an interaction with the recipient
+    pub one_sided: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentResult {
+    pub address: String,
+    pub tx_id: u64,
+    pub is_success: bool,
+    pub failure_message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// Writes the stored transactions and totals per day to a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTransactions {
+    /// A name of the file in the exports directory of the launchpad
+    pub file_name: String,
+    pub format: ExportFormat,
+    /// Inclusive, UTC
+    pub from: Option<NaiveDateTime>,
+    /// Exclusive, UTC
+    pub to: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub files: Vec<PathBuf>,
+    pub transactions: usize,
+    pub days: usize,
 }
@@ -0,0 +1,102 @@
+use tari_launchpad_protocol::settings::{
+    default_mining_threads,
+    LaunchpadSettings,
+    SettingsPatch,
+    Sha3MinerConfig,
+    MAX_COINBASE_EXTRA,
+    MAX_MINING_THREADS,
+};
+
+const MONERO_ADDRESS: &str =
+    "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A";
+
+#[test]
+fn test_monero_mining_address_cleared() {
+    let mut settings = LaunchpadSettings::default();
+    let patch = SettingsPatch {
+        monero_mining_address: Some(MONERO_ADDRESS.into()),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+    assert_eq!(settings.xmrig.as_ref().unwrap().monero_mining_address, MONERO_ADDRESS);
+
+    let patch = SettingsPatch {
+        monero_mining_address: Some(String::new()),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+    assert!(settings.xmrig.as_ref().unwrap().monero_mining_address.is_empty());
+
+    let patch = SettingsPatch {
+        monero_mining_address: Some("not an address".into()),
+        ..Default::default()
+    };
+    assert!(settings.apply_patch(patch).is_err());
+}
+
+#[test]
+fn test_mining_threads() {
+    let mut config = Sha3MinerConfig::default();
+    assert_eq!(config.num_mining_threads, 0);
+    assert_eq!(config.mining_threads(), default_mining_threads());
+    assert!(config.mining_threads() >= 1);
+
+    config.num_mining_threads = 3;
+    assert_eq!(config.mining_threads(), 3);
+}
+
+#[test]
+fn test_sha3_miner_validation() {
+    let mut settings = LaunchpadSettings::default();
+    let patch = SettingsPatch {
+        coinbase_extra: Some("x".repeat(MAX_COINBASE_EXTRA + 1)),
+        ..Default::default()
+    };
+    assert!(settings.apply_patch(patch).is_err());
+    assert!(settings.sha3_miner.is_none());
+
+    let patch = SettingsPatch {
+        num_mining_threads: Some(MAX_MINING_THREADS + 1),
+        ..Default::default()
+    };
+    assert!(settings.apply_patch(patch).is_err());
+
+    let patch = SettingsPatch {
+        coinbase_extra: Some("x".repeat(MAX_COINBASE_EXTRA)),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+    assert_eq!(settings.sha3_miner.unwrap().coinbase_extra.len(), MAX_COINBASE_EXTRA);
+}
+
+#[test]
+fn test_sha3_miner_patch_merged() {
+    let mut settings = LaunchpadSettings::default();
+    let patch = SettingsPatch {
+        num_mining_threads: Some(2),
+        coinbase_extra: Some("launchpad".into()),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+
+    let patch = SettingsPatch {
+        mine_on_tip_only: Some(false),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+    let sha3_miner = settings.sha3_miner.as_ref().unwrap();
+    assert_eq!(sha3_miner.num_mining_threads, 2);
+    assert!(!sha3_miner.mine_on_tip_only);
+    assert_eq!(sha3_miner.coinbase_extra, "launchpad");
+
+    let patch = SettingsPatch {
+        num_mining_threads: Some(0),
+        coinbase_extra: Some(String::new()),
+        ..Default::default()
+    };
+    settings.apply_patch(patch).unwrap();
+    let sha3_miner = settings.sha3_miner.as_ref().unwrap();
+    assert_eq!(sha3_miner.mining_threads(), default_mining_threads());
+    assert!(!sha3_miner.mine_on_tip_only);
+    assert!(sha3_miner.coinbase_extra.is_empty());
+}
@@ -49,6 +49,9 @@ impl SdmWorker {
                 Reaction::Delta(delta) => {
                     state.apply(delta);
                 },
+                Reaction::ActionFailed { action, reason } => {
+                    log::warn!("The action {} failed: {}", action, reason);
+                },
             }
         }
     }
@@ -21,28 +21,44 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use anyhow::Error;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+use anyhow::{anyhow, Error};
 use tari_launchpad_protocol::{
     container::{TaskDelta, TaskId},
-    launchpad::{Action, LaunchpadAction, LaunchpadDelta, LaunchpadState, Reaction},
-    settings::WalletConfig,
+    launchpad::{Action, ActionKind, LaunchpadAction, LaunchpadDelta, LaunchpadState, Reaction},
+    mining::{MinerKind, MiningDelta},
+    settings::{LaunchpadSettings, MONERO_PASSWORD, TOR_CONTROL_PASSWORD, WALLET_PASSWORD},
+    wallet::{SeedWordsBackup, WalletDelta, WalletRestore, SEED_WORDS_COUNT},
 };
-use tari_sdm::{ids::ManagedTask, Report, ReportEnvelope, SdmScope};
+use tari_sdm::{ids::ManagedTask, process::ManagedProcess, Report, ReportEnvelope, SdmScope, TaskHandle};
+use tari_utilities::Hidden;
 use tokio::{select, sync::mpsc};
 
 use crate::{
+    export,
+    history::TransactionStore,
     resources::{
-        config::{LaunchpadProtocol, LaunchpadSettings},
+        config::{LaunchpadOuterEvent, LaunchpadProtocol},
         files::Configurator,
         images,
         networks,
         volumes,
     },
+    secrets::{self, SecretStore},
     wallet_grpc::WalletGrpc,
 };
 
+/// The length of the generated passwords.
+const PASSWORD_LENGTH: usize = 32;
+
 pub struct LaunchpadBus {
-    // pub handle: JoinHandle<()>,
+    /// Finishes when the worker has stopped all the tasks
+    pub handle: JoinHandle<()>,
     pub incoming: mpsc::UnboundedSender<Action>,
     pub outgoing: mpsc::UnboundedReceiver<Reaction>,
 }
@@ -51,8 +67,13 @@ impl LaunchpadBus {
     pub fn start() -> Result<Self, Error> {
         let (in_tx, in_rx) = mpsc::unbounded_channel();
         let (out_tx, out_rx) = mpsc::unbounded_channel();
-        std::thread::spawn(move || LaunchpadWorker::create_and_run(in_rx, out_tx));
+        let handle = thread::spawn(move || {
+            if let Err(err) = LaunchpadWorker::create_and_run(in_rx, out_tx) {
+                log::error!("Launchpad worker failed: {}", err);
+            }
+        });
         Ok(Self {
+            handle,
             incoming: in_tx,
             outgoing: out_rx,
         })
@@ -61,12 +82,18 @@ impl LaunchpadBus {
 
 pub struct LaunchpadWorker {
     state: LaunchpadState,
+    configurator: Configurator,
     scope: SdmScope<LaunchpadProtocol>,
+    tasks: HashMap<TaskId, TaskHandle<LaunchpadProtocol>>,
     in_rx: mpsc::UnboundedReceiver<Action>,
     // TODO: Share the sender with the wallet
     out_tx: mpsc::UnboundedSender<Reaction>,
     wallet_task_id: TaskId,
     wallet_grpc: Option<WalletGrpc>,
+    history: Arc<TransactionStore>,
+    /// Is set when the user unlocked the store in this session.
+    secrets: Option<SecretStore>,
+    stopped: bool,
 }
 
 impl LaunchpadWorker {
@@ -75,65 +102,98 @@ impl LaunchpadWorker {
         in_rx: mpsc::UnboundedReceiver<Action>,
         out_tx: mpsc::UnboundedSender<Reaction>,
     ) -> Result<(), Error> {
+        let mut configurator = Configurator::init()?;
+        // Tasks can't be replaced in the running scope
+        let runtime = match configurator.read_settings().await {
+            Ok(settings) if settings.native_processes => images::Runtime::Native,
+            Ok(_) => images::Runtime::Docker,
+            Err(err) => {
+                log::error!("Can't read the settings to select the runtime: {}", err);
+                images::Runtime::Docker
+            },
+        };
         let mut scope = SdmScope::connect("esmeralda")?;
-        scope.add_network(networks::LocalNet::default())?;
-        scope.add_volume(volumes::SharedVolume::default())?;
-        scope.add_volume(volumes::SharedGrafanaVolume::default())?;
+        let handles = vec![
+            scope.add_network(networks::LocalNet::default())?,
+            scope.add_volume(volumes::SharedVolume::default())?,
+            scope.add_volume(volumes::SharedGrafanaVolume::default())?,
+            scope.add_image(images::Tor::new(runtime))?,
+            add_app(&mut scope, runtime, images::TariBaseNode::new(runtime))?,
+            add_app(&mut scope, runtime, images::TariWallet::new(runtime))?,
+            add_app(&mut scope, runtime, images::TariSha3Miner::new(runtime))?,
+            scope.add_image(images::Loki::default())?,
+            scope.add_image(images::Promtail::default())?,
+            scope.add_image(images::Grafana::default())?,
+            add_app(&mut scope, runtime, images::MmProxy::new(runtime))?,
+            scope.add_image(images::Monerod::default())?,
+            scope.add_image(images::XMRig::default())?,
+        ];
+        scope.start()?;
+        let tasks = handles
+            .into_iter()
+            .map(|handle| (handle.task_id().clone(), handle))
+            .collect();
 
-        scope.add_image(images::Tor::default())?;
-        scope.add_image(images::TariBaseNode::default())?;
-        scope.add_image(images::TariWallet::default())?;
-        scope.add_image(images::TariSha3Miner::default())?;
-
-        scope.add_image(images::Loki::default())?;
-        scope.add_image(images::Promtail::default())?;
-        scope.add_image(images::Grafana::default())?;
-
-        scope.add_image(images::MmProxy::default())?;
-        scope.add_image(images::Monerod::default())?;
-        scope.add_image(images::XMRig::default())?;
-
-        let state = LaunchpadState::new();
+        let mut state = LaunchpadState::new();
+        state.secrets_exist = configurator.secrets_path().exists();
+        let history = Arc::new(TransactionStore::open(configurator.history_path()).await?);
 
         let worker = LaunchpadWorker {
             state,
+            configurator,
             scope,
+            tasks,
             in_rx,
             out_tx,
             wallet_task_id: images::TariWallet::id(),
             wallet_grpc: None,
+            history,
+            secrets: None,
+            stopped: false,
         };
         worker.entrypoint().await;
         Ok(())
     }
 
     async fn entrypoint(mut self) {
-        self.load_configuration().await.ok();
+        let order = self.scope.order().to_vec();
+        self.apply_delta(LaunchpadDelta::TasksOrder(order));
+        if let Err(err) = self.load_configuration().await {
+            log::error!("Can't load the configuration: {}", err);
+        }
+        if let Err(err) = self.resume_restore().await {
+            log::error!("Can't resume the restore of the wallet: {}", err);
+        }
+        self.refresh_backup();
         // TODO: Watch for the config file changes
         let config = self.state.config.clone();
         self.scope.set_config(Some(config)).ok();
-        loop {
+        while !self.stopped {
             if let Err(err) = self.step().await {
                 log::error!("Bus failed: {}", err);
             }
         }
     }
 
+    async fn shutdown(&mut self) -> Result<(), Error> {
+        log::info!("Shutting down the launchpad...");
+        self.stopped = true;
+        self.wallet_grpc.take();
+        self.scope.stop().await?;
+        // Delivers the last statuses of the tasks
+        while let Some(report) = self.scope.try_recv() {
+            self.process_report(report).await?;
+        }
+        log::info!("All the tasks are stopped");
+        Ok(())
+    }
+
     async fn load_configuration(&mut self) -> Result<(), Error> {
-        let mut configurator = Configurator::init()?;
-        let data_directory = configurator.base_path().clone();
-        configurator.repair_configuration().await?;
-        let wallet_config = WalletConfig {
-            password: "123".to_string().into(),
-        };
-        let config = LaunchpadSettings {
-            data_directory,
-            with_monitoring: true,
-            tor_control_password: "tari".to_string().into(), // create_password(16).into(),
-            wallet: Some(wallet_config),
-            ..Default::default()
-        };
-        self.apply_delta(LaunchpadDelta::UpdateConfig(config));
+        self.configurator.repair_configuration().await?;
+        let settings = self.configurator.read_settings().await?;
+        // Writes the settings back to store defaults and migrations
+        self.configurator.write_settings(&settings).await?;
+        self.apply_delta(LaunchpadDelta::UpdateConfig(settings));
         Ok(())
     }
 
@@ -142,7 +202,10 @@ impl LaunchpadWorker {
             // TODO: Watch config changes here
             action = self.in_rx.recv() => {
                 if let Some(action) = action {
-                    self.process_incoming(action).await?;
+                    self.process_incoming(action).await;
+                } else {
+                    // All the clients have gone
+                    self.shutdown().await?;
                 }
             }
             report = self.scope.recv() => {
@@ -154,9 +217,19 @@ impl LaunchpadWorker {
         Ok(())
     }
 
-    async fn process_incoming(&mut self, incoming: Action) -> Result<(), Error> {
+    async fn process_incoming(&mut self, incoming: Action) {
         match incoming {
-            Action::Action(action) => self.process_action(action).await,
+            Action::Action(action) => {
+                let kind = ActionKind::from(&action);
+                if let Err(err) = self.process_action(action).await {
+                    log::error!("The action {} failed: {}", kind, err);
+                    // The clients show why the action was rejected
+                    self.send(Reaction::ActionFailed {
+                        action: kind,
+                        reason: err.to_string(),
+                    });
+                }
+            },
         }
     }
 
@@ -171,10 +244,252 @@ impl LaunchpadWorker {
                 let config = self.state.config.clone();
                 self.scope.set_config(Some(config))?;
             },
+            LaunchpadAction::UpdateSettings(patch) => {
+                let mut settings = self
+                    .state
+                    .config
+                    .settings
+                    .clone()
+                    .ok_or_else(|| anyhow!("Settings are not loaded"))?;
+                if let Some(password) = patch.monero_password.clone() {
+                    let store = self
+                        .secrets
+                        .as_mut()
+                        .ok_or_else(|| anyhow!("The secret store is locked"))?;
+                    store.insert(MONERO_PASSWORD, password);
+                    store.save().await?;
+                }
+                settings.apply_patch(patch)?;
+                self.fill_secrets(&mut settings);
+                self.configurator.write_settings(&settings).await?;
+                self.apply_delta(LaunchpadDelta::UpdateConfig(settings));
+                let config = self.state.config.clone();
+                self.scope.set_config(Some(config))?;
+            },
+            LaunchpadAction::Shutdown => {
+                self.shutdown().await?;
+            },
+            LaunchpadAction::RestartTask(task_id) => {
+                self.task(&task_id)?.restart()?;
+            },
+            LaunchpadAction::StopTask(task_id) => {
+                self.task(&task_id)?.stop()?;
+            },
+            LaunchpadAction::RepullTask(task_id) => {
+                self.task(&task_id)?.repull()?;
+            },
+            LaunchpadAction::RecreateTask(task_id) => {
+                self.task(&task_id)?.recreate()?;
+            },
+            LaunchpadAction::QueryTransactions(query) => {
+                let page = self.history.query(query).await;
+                self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::UpdateHistory(page)));
+            },
+            LaunchpadAction::ExportTransactions(request) => {
+                let path = export::export_path(&self.configurator.exports_path(), &request.file_name)?;
+                let summary =
+                    export::export_transactions(&self.history, &path, request.format, request.from, request.to).await?;
+                self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::Exported(summary)));
+            },
+            LaunchpadAction::RevealSeedWords => {
+                self.reveal_seed_words().await?;
+            },
+            LaunchpadAction::ConfirmSeedWordsBackup => {
+                self.confirm_seed_words_backup().await?;
+            },
+            LaunchpadAction::RestoreWallet(restore) => {
+                self.restore_wallet(restore).await?;
+            },
+            LaunchpadAction::UnlockSecrets(passphrase) => {
+                self.unlock_secrets(passphrase).await?;
+            },
+            LaunchpadAction::RotateTorPassword => {
+                self.rotate_tor_password().await?;
+            },
+            LaunchpadAction::WalletAction(action) => {
+                self.wallet_grpc
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("The wallet is not running"))?
+                    .send(action)?;
+            },
+        }
+        Ok(())
+    }
+
+    async fn unlock_secrets(&mut self, passphrase: Hidden<String>) -> Result<(), Error> {
+        if self.secrets.is_some() {
+            return Err(anyhow!("The secret store is already unlocked"));
+        }
+        let path = self.configurator.secrets_path();
+        let mut store = SecretStore::unlock(path, passphrase.reveal()).await?;
+        // The passwords of the old settings are imported once
+        let legacy_secrets = self.configurator.take_legacy_secrets();
+        let has_legacy_secrets = !legacy_secrets.is_empty();
+        for (name, value) in legacy_secrets {
+            // The old Tor password was the same for all the users.
+            // Tor doesn't keep it, so a new one is generated instead.
+            if name != TOR_CONTROL_PASSWORD && store.get(&name).is_none() {
+                store.insert(&name, value);
+            }
+        }
+        if store.get(WALLET_PASSWORD).is_none() {
+            // A wallet created before the secret store is encrypted
+            // with the password that was hard-coded then
+            let password = if has_legacy_secrets || self.configurator.has_wallet() {
+                Hidden::from("123".to_string())
+            } else {
+                secrets::create_password(PASSWORD_LENGTH)
+            };
+            store.insert(WALLET_PASSWORD, password);
+        }
+        if store.get(MONERO_PASSWORD).is_none() {
+            store.insert(MONERO_PASSWORD, Hidden::from(String::new()));
+        }
+        // Generated on the first run, Tor gets its hash only
+        if store.get(TOR_CONTROL_PASSWORD).is_none() {
+            store.insert(TOR_CONTROL_PASSWORD, secrets::create_password(PASSWORD_LENGTH));
+        }
+        store.save().await?;
+        self.secrets = Some(store);
+        if let Some(mut settings) = self.state.config.settings.clone() {
+            self.fill_secrets(&mut settings);
+            if has_legacy_secrets {
+                // Removes the plain text passwords from the settings file
+                self.configurator.write_settings(&settings).await?;
+            }
+            self.apply_delta(LaunchpadDelta::UpdateConfig(settings));
         }
+        self.apply_delta(LaunchpadDelta::SecretsUnlocked);
+        let config = self.state.config.clone();
+        self.scope.set_config(Some(config))?;
         Ok(())
     }
 
+    /// Replaces the Tor control password. The Tor container is recreated
+    /// with the new hash and the containers that use Tor with the password.
+    async fn rotate_tor_password(&mut self) -> Result<(), Error> {
+        let store = self
+            .secrets
+            .as_mut()
+            .ok_or_else(|| anyhow!("The secret store is locked"))?;
+        store.insert(TOR_CONTROL_PASSWORD, secrets::create_password(PASSWORD_LENGTH));
+        store.save().await?;
+        log::info!("The Tor control password is rotated");
+        if let Some(mut settings) = self.state.config.settings.clone() {
+            self.fill_secrets(&mut settings);
+            self.apply_delta(LaunchpadDelta::UpdateConfig(settings));
+            let config = self.state.config.clone();
+            self.scope.set_config(Some(config))?;
+        }
+        Ok(())
+    }
+
+    /// Sets the values of the secrets referenced by the settings.
+    fn fill_secrets(&self, settings: &mut LaunchpadSettings) {
+        if let Some(store) = self.secrets.as_ref() {
+            for secret in settings.secrets_mut() {
+                if let Some(value) = store.get(&secret.name) {
+                    secret.value = Some(value.clone());
+                }
+            }
+        }
+    }
+
+    /// Tracks the seed words file that the new wallet creates.
+    fn refresh_backup(&mut self) {
+        if self.state.wallet.backup == SeedWordsBackup::NotFound && self.configurator.has_seed_words() {
+            self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::UpdateBackup(
+                SeedWordsBackup::Pending,
+            )));
+        }
+    }
+
+    async fn reveal_seed_words(&mut self) -> Result<(), Error> {
+        let backup = self.state.wallet.backup;
+        if backup != SeedWordsBackup::Pending {
+            return Err(anyhow!("The seed words can't be revealed: {:?}", backup));
+        }
+        let words = self.configurator.read_seed_words().await?;
+        self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::UpdateBackup(
+            SeedWordsBackup::Revealed,
+        )));
+        // The words are not applied to the state to not send them to other clients
+        let delta = LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(words.into()));
+        self.send(Reaction::Delta(delta));
+        Ok(())
+    }
+
+    async fn confirm_seed_words_backup(&mut self) -> Result<(), Error> {
+        let backup = self.state.wallet.backup;
+        if backup != SeedWordsBackup::Revealed {
+            return Err(anyhow!("The seed words were not revealed: {:?}", backup));
+        }
+        self.configurator.delete_seed_words().await?;
+        self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::UpdateBackup(
+            SeedWordsBackup::Done,
+        )));
+        Ok(())
+    }
+
+    async fn restore_wallet(&mut self, restore: WalletRestore) -> Result<(), Error> {
+        let words = restore.seed_words.reveal();
+        let valid = words.len() == SEED_WORDS_COUNT && words.iter().all(|word| word.chars().all(char::is_alphabetic));
+        if !valid {
+            return Err(anyhow!("A restore requires {} seed words", SEED_WORDS_COUNT));
+        }
+        let wallet_active = self
+            .state
+            .containers
+            .get(&self.wallet_task_id)
+            .map(|state| state.status.is_active())
+            .unwrap_or_default();
+        if wallet_active {
+            return Err(anyhow!("The wallet has to be stopped before the restore"));
+        }
+        let backup_dir = self.configurator.archive_wallet().await?;
+        log::info!("The current wallet is moved to {}", backup_dir.display());
+        self.configurator.write_restore_words(words).await?;
+        self.apply_delta(LaunchpadDelta::WalletDelta(WalletDelta::UpdateBackup(
+            SeedWordsBackup::NotFound,
+        )));
+        self.state.config.restore = Some(restore);
+        let config = self.state.config.clone();
+        self.scope.set_config(Some(config))?;
+        Ok(())
+    }
+
+    /// Continues a restore interrupted by a restart of the launchpad.
+    /// The birthday is not stored, so the recovery scans the whole chain.
+    async fn resume_restore(&mut self) -> Result<(), Error> {
+        if self.configurator.has_pending_restore() {
+            log::info!("Resuming the restore of the wallet");
+            let words = self.configurator.read_restore_words().await?;
+            self.state.config.restore = Some(WalletRestore {
+                seed_words: words.into(),
+                birthday: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Leaves the recovery mode once the recovered wallet is ready.
+    async fn finish_restore(&mut self) -> Result<(), Error> {
+        if self.state.config.restore.is_some() {
+            self.configurator.delete_restore_words().await?;
+            self.state.config.restore = None;
+            // Restarts the recovered wallet in the normal mode
+            let config = self.state.config.clone();
+            self.scope.set_config(Some(config))?;
+        }
+        Ok(())
+    }
+
+    fn task(&self, task_id: &TaskId) -> Result<&TaskHandle<LaunchpadProtocol>, Error> {
+        self.tasks
+            .get(task_id)
+            .ok_or_else(|| anyhow!("Unknown task {}", task_id))
+    }
+
     fn apply_delta(&mut self, delta: LaunchpadDelta) {
         self.state.apply(delta.clone());
         let reaction = Reaction::Delta(delta);
@@ -199,24 +514,33 @@ impl LaunchpadWorker {
             },
             Report::Delta(delta) => {
                 if report.task_id == self.wallet_task_id {
-                    self.check_wallet_grpc(&delta);
+                    self.check_wallet_grpc(&delta).await;
                 }
+                self.check_miner_stopped(&report.task_id, &delta);
                 let delta = LaunchpadDelta::TaskDelta {
                     id: report.task_id,
                     delta,
                 };
                 self.apply_delta(delta);
             },
-            Report::Extras(_) => {},
+            Report::Extras(event) => match event {
+                LaunchpadOuterEvent::MiningDelta(delta) => {
+                    self.apply_delta(LaunchpadDelta::MiningDelta(delta));
+                },
+            },
         }
         Ok(())
     }
 
-    fn check_wallet_grpc(&mut self, delta: &TaskDelta) {
+    async fn check_wallet_grpc(&mut self, delta: &TaskDelta) {
         if let TaskDelta::UpdateStatus(status) = delta {
             if status.is_ready() {
+                if let Err(err) = self.finish_restore().await {
+                    log::error!("Can't finish the restore of the wallet: {}", err);
+                }
+                self.refresh_backup();
                 if self.wallet_grpc.is_none() {
-                    let grpc = WalletGrpc::new(self.out_tx.clone());
+                    let grpc = WalletGrpc::new(self.out_tx.clone(), self.history.clone());
                     self.wallet_grpc = Some(grpc);
                 }
             } else {
@@ -228,4 +552,34 @@ impl LaunchpadWorker {
             }
         }
     }
+
+    fn check_miner_stopped(&mut self, task_id: &TaskId, delta: &TaskDelta) {
+        let miner = if *task_id == images::TariSha3Miner::id() {
+            MinerKind::Sha3
+        } else if *task_id == images::XMRig::id() {
+            MinerKind::Xmrig
+        } else {
+            return;
+        };
+        if let TaskDelta::UpdateStatus(status) = delta {
+            if !status.is_ready() {
+                self.apply_delta(LaunchpadDelta::MiningDelta(MiningDelta::Stopped(miner)));
+            }
+        }
+    }
+}
+
+/// Adds a Tari application as a container or as a process of the host.
+fn add_app<A>(
+    scope: &mut SdmScope<LaunchpadProtocol>,
+    runtime: images::Runtime,
+    app: A,
+) -> Result<TaskHandle<LaunchpadProtocol>, Error>
+where
+    A: ManagedProcess<Protocol = LaunchpadProtocol> + ManagedTask,
+{
+    match runtime {
+        images::Runtime::Docker => scope.add_image(app),
+        images::Runtime::Native => scope.add_process(app),
+    }
 }
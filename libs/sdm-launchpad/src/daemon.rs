@@ -0,0 +1,216 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+//! The control endpoint of the launchpad running as a daemon.
+//!
+//! Clients are attached over a unix socket and exchange JSON lines:
+//! actions from the client and reactions from the daemon. The first
+//! reaction is the full state. Clients can detach at any time and
+//! the containers keep running until the `Shutdown` action.
+
+use std::{
+    fs::Permissions,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Error};
+use serde::Serialize;
+use tari_launchpad_protocol::launchpad::{Action, Reaction};
+use tokio::{
+    fs,
+    io::{AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    select,
+    sync::{broadcast, mpsc},
+    time::sleep,
+};
+
+use crate::mirror::{Mirror, Subscription, ACCEPT_RETRY_DELAY};
+
+/// Checks that a daemon accepts connections on the socket.
+pub async fn is_running(path: &Path) -> bool {
+    UnixStream::connect(path).await.is_ok()
+}
+
+/// Serves the bus on the socket until it stops and closes the `outgoing` channel.
+pub async fn serve(
+    path: PathBuf,
+    incoming: mpsc::UnboundedSender<Action>,
+    mut outgoing: mpsc::UnboundedReceiver<Reaction>,
+) -> Result<(), Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+        // The socket is created with the permissions of the umask,
+        // so the directory keeps others away until they are changed
+        fs::set_permissions(dir, Permissions::from_mode(0o700)).await?;
+    }
+    if path.exists() {
+        if is_running(&path).await {
+            return Err(anyhow!("The launchpad daemon is already running at {}", path.display()));
+        }
+        // The socket of a crashed daemon
+        fs::remove_file(&path).await?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    // Only the owner controls the daemon
+    fs::set_permissions(&path, Permissions::from_mode(0o600)).await?;
+    log::info!("The launchpad daemon listens on {}", path.display());
+    let mirror = Mirror::new();
+    loop {
+        select! {
+            reaction = outgoing.recv() => {
+                if let Some(reaction) = reaction {
+                    mirror.apply(reaction)?;
+                } else {
+                    // The bus has stopped
+                    break;
+                }
+            }
+            connection = listener.accept() => {
+                let stream = match connection {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        // Running out of descriptors is temporary, so the daemon keeps serving
+                        log::error!("Can't accept a client of the daemon: {}", err);
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                        continue;
+                    },
+                };
+                let subscription = mirror.subscribe(incoming.clone())?;
+                tokio::spawn(async move {
+                    if let Err(err) = serve_client(stream, subscription).await {
+                        log::warn!("A client of the daemon failed: {}", err);
+                    }
+                });
+            }
+        }
+    }
+    fs::remove_file(&path).await.ok();
+    Ok(())
+}
+
+async fn serve_client(stream: UnixStream, subscription: Subscription) -> Result<(), Error> {
+    log::info!("A client attached to the daemon");
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    let Subscription {
+        state,
+        mut reactions,
+        mut replies,
+        actions,
+    } = subscription;
+    write_line(&mut writer, &Reaction::State(state)).await?;
+    loop {
+        select! {
+            Some(reply) = replies.recv() => {
+                write_line(&mut writer, &reply).await?;
+            }
+            reaction = reactions.recv() => {
+                match reaction {
+                    Ok(reaction) => {
+                        write_line(&mut writer, &reaction).await?;
+                    },
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        // The client has to attach again to get the actual state
+                        return Err(anyhow!("The client missed {} reactions", skipped));
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    },
+                }
+            }
+            line = lines.next_line() => {
+                if let Some(line) = line? {
+                    match serde_json::from_str::<Action>(&line) {
+                        Ok(action) => {
+                            actions.send(action)?;
+                        },
+                        Err(err) => {
+                            log::error!("Can't parse an action of the client: {}", err);
+                        },
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+    log::info!("A client detached from the daemon");
+    Ok(())
+}
+
+async fn write_line<W, T>(writer: &mut W, value: &T) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let mut data = serde_json::to_vec(value)?;
+    data.push(b'\n');
+    writer.write_all(&data).await?;
+    Ok(())
+}
+
+/// A connection to the daemon with the same channels as the `LaunchpadBus` has.
+/// Dropping of the client detaches it from the daemon.
+pub struct DaemonClient {
+    pub incoming: mpsc::UnboundedSender<Action>,
+    /// Is closed when the daemon has stopped.
+    pub outgoing: mpsc::UnboundedReceiver<Reaction>,
+}
+
+impl DaemonClient {
+    pub async fn attach(path: &Path) -> Result<Self, Error> {
+        let stream = UnixStream::connect(path).await?;
+        let (reader, mut writer) = stream.into_split();
+        let (in_tx, mut in_rx) = mpsc::unbounded_channel::<Action>();
+        let (out_tx, out_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(action) = in_rx.recv().await {
+                if let Err(err) = write_line(&mut writer, &action).await {
+                    log::error!("Can't send an action to the daemon: {}", err);
+                    break;
+                }
+            }
+        });
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match serde_json::from_str(&line) {
+                    Ok(reaction) => {
+                        if out_tx.send(reaction).is_err() {
+                            break;
+                        }
+                    },
+                    Err(err) => {
+                        log::error!("Can't parse a reaction of the daemon: {}", err);
+                    },
+                }
+            }
+        });
+        Ok(Self {
+            incoming: in_tx,
+            outgoing: out_rx,
+        })
+    }
+}
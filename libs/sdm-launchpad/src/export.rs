@@ -0,0 +1,210 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{anyhow, Error};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use tari_launchpad_protocol::wallet::{normalize, ExportFormat, ExportSummary, TransactionQuery, WalletTransaction};
+use tari_utilities::hex::to_hex;
+use tokio::fs;
+
+use crate::history::TransactionStore;
+
+/// Amounts of a single day in microtari.
+/// Cancelled and rejected transactions are not counted.
+/// The days are in the time zone of the export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyTotal {
+    pub date: NaiveDate,
+    pub transactions: usize,
+    pub received: u64,
+    pub sent: u64,
+    pub mined: u64,
+    pub fees: u64,
+}
+
+/// Transactions ordered from the oldest to the newest with totals per day.
+#[derive(Debug, Clone, Serialize)]
+pub struct Ledger {
+    pub transactions: Vec<WalletTransaction>,
+    pub daily_totals: Vec<DailyTotal>,
+}
+
+impl Ledger {
+    /// Groups the transactions by the days in the `tz`.
+    pub fn new<Tz: TimeZone>(mut transactions: Vec<WalletTransaction>, tz: &Tz) -> Self {
+        transactions.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.tx_id.cmp(&b.tx_id)));
+        let mut totals: BTreeMap<NaiveDate, DailyTotal> = BTreeMap::new();
+        for transaction in &transactions {
+            let status = normalize(&transaction.status);
+            if status == "cancelled" || status == "rejected" {
+                continue;
+            }
+            let date = tz.from_utc_datetime(&transaction.timestamp).date_naive();
+            let total = totals.entry(date).or_insert_with(|| DailyTotal {
+                date,
+                transactions: 0,
+                received: 0,
+                sent: 0,
+                mined: 0,
+                fees: 0,
+            });
+            total.transactions += 1;
+            if transaction.is_coinbase {
+                total.mined += transaction.amount;
+            } else if normalize(&transaction.direction) == "outbound" {
+                total.sent += transaction.amount;
+                total.fees += transaction.fee;
+            } else {
+                total.received += transaction.amount;
+            }
+        }
+        Self {
+            transactions,
+            daily_totals: totals.into_values().collect(),
+        }
+    }
+
+    pub fn transactions_csv(&self) -> String {
+        let mut csv =
+            String::from("timestamp,tx_id,direction,status,amount,fee,is_coinbase,source_pk,dest_pk,message\n");
+        for tx in &self.transactions {
+            let row = [
+                Utc.from_utc_datetime(&tx.timestamp).to_rfc3339(),
+                tx.tx_id.clone(),
+                tx.direction.clone(),
+                tx.status.clone(),
+                tx.amount.to_string(),
+                tx.fee.to_string(),
+                tx.is_coinbase.to_string(),
+                to_hex(&tx.source_pk),
+                to_hex(&tx.dest_pk),
+                tx.message.clone(),
+            ];
+            push_row(&mut csv, &row);
+        }
+        csv
+    }
+
+    pub fn daily_totals_csv(&self) -> String {
+        let mut csv = String::from("date,transactions,received,sent,mined,fees\n");
+        for total in &self.daily_totals {
+            let row = [
+                total.date.to_string(),
+                total.transactions.to_string(),
+                total.received.to_string(),
+                total.sent.to_string(),
+                total.mined.to_string(),
+                total.fees.to_string(),
+            ];
+            push_row(&mut csv, &row);
+        }
+        csv
+    }
+}
+
+fn push_row(csv: &mut String, row: &[String]) {
+    let row: Vec<_> = row.iter().map(|field| escape(field)).collect();
+    csv.push_str(&row.join(","));
+    csv.push('\n');
+}
+
+fn escape(field: &str) -> String {
+    // Spreadsheets run fields that look like formulas. Messages are set
+    // by senders, so such fields are prefixed to be read as text.
+    let field = if field.starts_with(|c| matches!(c, '=' | '+' | '-' | '@' | '\t' | '\r')) {
+        Cow::Owned(format!("'{}", field))
+    } else {
+        Cow::Borrowed(field)
+    };
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Resolves the file of an export requested over the bus.
+/// Only a plain file name is accepted, so clients can't write outside of the `dir`.
+pub fn export_path(dir: &Path, file_name: &str) -> Result<PathBuf, Error> {
+    let mut components = Path::new(file_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Ok(dir.join(name)),
+        _ => Err(anyhow!("Invalid name of the export file: {}", file_name)),
+    }
+}
+
+/// Writes the ledger of the stored transactions from the inclusive `from` to the exclusive `to`.
+/// The CSV format uses a separate `<name>_daily.csv` file for the totals.
+pub async fn export_transactions(
+    store: &TransactionStore,
+    path: &Path,
+    format: ExportFormat,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<ExportSummary, Error> {
+    let query = TransactionQuery {
+        from,
+        to,
+        limit: usize::MAX,
+        ..Default::default()
+    };
+    let page = store.query(query).await;
+    let ledger = Ledger::new(page.transactions, &Local);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).await?;
+    }
+    let files = match format {
+        ExportFormat::Csv => {
+            let daily_path = daily_path(path);
+            fs::write(path, ledger.transactions_csv()).await?;
+            fs::write(&daily_path, ledger.daily_totals_csv()).await?;
+            vec![path.to_path_buf(), daily_path]
+        },
+        ExportFormat::Json => {
+            let data = serde_json::to_string_pretty(&ledger)?;
+            fs::write(path, data).await?;
+            vec![path.to_path_buf()]
+        },
+    };
+    log::info!("Exported {} transactions to {:?}", ledger.transactions.len(), files);
+    Ok(ExportSummary {
+        files,
+        transactions: ledger.transactions.len(),
+        days: ledger.daily_totals.len(),
+    })
+}
+
+fn daily_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}_daily.csv", stem))
+}
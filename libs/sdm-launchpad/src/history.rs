@@ -0,0 +1,149 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::Error;
+use tari_launchpad_protocol::wallet::{TransactionQuery, TransactionsPage, WalletTransaction};
+use tokio::{
+    fs::{self, OpenOptions},
+    io::AsyncWriteExt,
+    sync::Mutex,
+};
+
+/// The local history of the wallet transactions.
+///
+/// Every change of a transaction is appended to the file as a JSON line.
+/// The file is compacted when the store is opened.
+pub struct TransactionStore {
+    path: PathBuf,
+    transactions: Mutex<HashMap<String, WalletTransaction>>,
+}
+
+impl TransactionStore {
+    /// Opens the store to write. It creates the directory or compacts the existing file.
+    pub async fn open(path: PathBuf) -> Result<Self, Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let store = Self::load(path).await?;
+        store.compact().await?;
+        Ok(store)
+    }
+
+    /// Reads the transactions without changing the file.
+    pub async fn load(path: PathBuf) -> Result<Self, Error> {
+        let mut transactions = HashMap::new();
+        if path.exists() {
+            let data = fs::read_to_string(&path).await?;
+            for line in data.lines().filter(|line| !line.trim().is_empty()) {
+                // A broken line could be left if the app crashed while writing
+                match serde_json::from_str::<WalletTransaction>(line) {
+                    Ok(transaction) => {
+                        transactions.insert(transaction.tx_id.clone(), transaction);
+                    },
+                    Err(err) => {
+                        log::warn!("Skipping a broken transaction record in {}: {}", path.display(), err);
+                    },
+                }
+            }
+        }
+        Ok(Self {
+            path,
+            transactions: Mutex::new(transactions),
+        })
+    }
+
+    async fn compact(&self) -> Result<(), Error> {
+        let transactions = self.transactions.lock().await;
+        let mut data = String::new();
+        for transaction in transactions.values() {
+            data.push_str(&serde_json::to_string(transaction)?);
+            data.push('\n');
+        }
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, data).await?;
+        fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+
+    /// Adds new transactions or merges changes into the known ones.
+    pub async fn upsert(&self, updates: Vec<WalletTransaction>) -> Result<(), Error> {
+        let mut transactions = self.transactions.lock().await;
+        let mut data = String::new();
+        for update in updates {
+            let merged = match transactions.get(&update.tx_id) {
+                Some(known) => merge(known, update),
+                None => update,
+            };
+            if transactions.get(&merged.tx_id) != Some(&merged) {
+                data.push_str(&serde_json::to_string(&merged)?);
+                data.push('\n');
+                transactions.insert(merged.tx_id.clone(), merged);
+            }
+        }
+        if !data.is_empty() {
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.path).await?;
+            file.write_all(data.as_bytes()).await?;
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn query(&self, query: TransactionQuery) -> TransactionsPage {
+        let transactions = self.transactions.lock().await;
+        let mut matched: Vec<_> = transactions
+            .values()
+            .filter(|transaction| query.matches(transaction))
+            .collect();
+        matched.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.tx_id.cmp(&a.tx_id)));
+        let total = matched.len();
+        let transactions = matched
+            .into_iter()
+            .skip(query.offset)
+            .take(query.page_size())
+            .cloned()
+            .collect();
+        TransactionsPage {
+            query,
+            total,
+            transactions,
+        }
+    }
+}
+
+/// Events of the wallet don't have fees and precise timestamps,
+/// but they have the coinbase flag that the grpc doesn't provide.
+fn merge(known: &WalletTransaction, update: WalletTransaction) -> WalletTransaction {
+    WalletTransaction {
+        event: if update.event.is_empty() {
+            known.event.clone()
+        } else {
+            update.event
+        },
+        fee: if update.fee == 0 { known.fee } else { update.fee },
+        is_coinbase: known.is_coinbase || update.is_coinbase,
+        timestamp: known.timestamp.min(update.timestamp),
+        ..update
+    }
+}
@@ -24,7 +24,16 @@
 #[cfg(feature = "tauri")]
 pub mod api;
 pub mod bus;
+#[cfg(unix)]
+pub mod daemon;
+pub mod export;
+pub mod history;
+#[cfg(any(unix, feature = "remote"))]
+mod mirror;
+#[cfg(feature = "remote")]
+pub mod remote;
 pub mod resources;
+pub mod secrets;
 #[cfg(feature = "tauri")]
 pub mod tauri;
 mod wallet_grpc;
@@ -0,0 +1,152 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Error};
+use tari_launchpad_protocol::{
+    launchpad::{Action, LaunchpadAction, LaunchpadDelta, LaunchpadState, Reaction},
+    wallet::{SeedWordsBackup, WalletDelta},
+};
+use tokio::sync::{broadcast, mpsc};
+
+/// The number of reactions that a slow client could fall behind.
+const REACTIONS_CAPACITY: usize = 1_024;
+
+/// The pause after a failed accept to not spin while the error lasts.
+pub const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// The state of the bus mirrored by a server to send it
+/// to the clients with the following reactions.
+#[derive(Clone)]
+pub struct Mirror {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    state: LaunchpadState,
+    reactions: broadcast::Sender<Reaction>,
+    /// The clients waiting for the revealed seed words.
+    seed_words_requests: Vec<mpsc::UnboundedSender<Reaction>>,
+}
+
+/// The snapshot of the state and the reactions that follow it.
+pub struct Subscription {
+    pub state: LaunchpadState,
+    pub reactions: broadcast::Receiver<Reaction>,
+    /// The reactions sent to this client only.
+    pub replies: mpsc::UnboundedReceiver<Reaction>,
+    pub actions: ClientActions,
+}
+
+/// Forwards the actions of a client to the bus.
+pub struct ClientActions {
+    inner: Weak<Mutex<Inner>>,
+    replies: mpsc::UnboundedSender<Reaction>,
+    incoming: mpsc::UnboundedSender<Action>,
+}
+
+impl ClientActions {
+    pub fn send(&self, action: Action) -> Result<(), Error> {
+        if let Action::Action(LaunchpadAction::RevealSeedWords) = action {
+            // The words are sent back to the client that has asked for them
+            let inner = self.inner.upgrade().ok_or_else(|| anyhow!("The server has stopped"))?;
+            let mut inner = lock(&inner)?;
+            if inner.state.wallet.backup == SeedWordsBackup::Pending {
+                inner.seed_words_requests.push(self.replies.clone());
+            }
+        }
+        self.incoming.send(action).map_err(|_| anyhow!("The bus has stopped"))
+    }
+}
+
+impl Mirror {
+    pub fn new() -> Self {
+        let (reactions, _) = broadcast::channel(REACTIONS_CAPACITY);
+        let inner = Inner {
+            state: LaunchpadState::new(),
+            reactions,
+            seed_words_requests: Vec::new(),
+        };
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    pub fn apply(&self, reaction: Reaction) -> Result<(), Error> {
+        let mut inner = self.lock()?;
+        if let Reaction::Delta(LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(_))) = reaction {
+            // The seed words are never kept in the mirror or broadcast
+            for request in inner.seed_words_requests.drain(..) {
+                request.send(reaction.clone()).ok();
+            }
+            return Ok(());
+        }
+        match reaction.clone() {
+            Reaction::State(state) => {
+                inner.state = state;
+            },
+            Reaction::Delta(delta) => {
+                inner.state.apply(delta);
+            },
+            Reaction::ActionFailed { .. } => {},
+        }
+        // No receivers is not an error here
+        inner.reactions.send(reaction).ok();
+        Ok(())
+    }
+
+    /// The snapshot and the receiver are taken together to not miss a delta.
+    /// The receiver is closed when all the copies of the mirror are dropped.
+    pub fn subscribe(&self, incoming: mpsc::UnboundedSender<Action>) -> Result<Subscription, Error> {
+        let inner = self.lock()?;
+        let (replies_tx, replies_rx) = mpsc::unbounded_channel();
+        Ok(Subscription {
+            state: inner.state.clone(),
+            reactions: inner.reactions.subscribe(),
+            replies: replies_rx,
+            actions: ClientActions {
+                inner: Arc::downgrade(&self.inner),
+                replies: replies_tx,
+                incoming,
+            },
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Inner>, Error> {
+        lock(&self.inner)
+    }
+}
+
+fn lock(inner: &Mutex<Inner>) -> Result<MutexGuard<'_, Inner>, Error> {
+    inner.lock().map_err(|_| anyhow!("The mirror of the state is poisoned"))
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self::new()
+    }
+}
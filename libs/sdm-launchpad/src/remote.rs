@@ -0,0 +1,219 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+//! A server that exposes the bus to remote clients.
+//!
+//! Actions and reactions are sent as JSON text messages over WebSocket.
+//! A client gets the full state on connect and the deltas after that.
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Error};
+use futures::{SinkExt, StreamExt};
+use tari_launchpad_protocol::launchpad::{Action, Reaction};
+use tari_utilities::Hidden;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    select,
+    sync::{broadcast, mpsc},
+    time::sleep,
+};
+use tokio_tungstenite::{
+    accept_hdr_async,
+    tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::{header::AUTHORIZATION, StatusCode},
+        Message,
+    },
+};
+
+use crate::mirror::{Mirror, Subscription, ACCEPT_RETRY_DELAY};
+
+pub const DEFAULT_REMOTE_PORT: u16 = 18_500;
+
+pub struct RemoteConfig {
+    /// It's the localhost by default. Use a tunnel or
+    /// bind to another interface to manage the host remotely.
+    pub address: SocketAddr,
+    /// Clients pass it as `Authorization: Bearer <token>`
+    /// header or as `token` parameter of the query.
+    pub token: Hidden<String>,
+}
+
+impl RemoteConfig {
+    pub fn new(token: Hidden<String>) -> Self {
+        Self {
+            address: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_REMOTE_PORT)),
+            token,
+        }
+    }
+}
+
+/// Serves the bus until it stops and closes the `outgoing` channel.
+pub async fn serve(
+    config: RemoteConfig,
+    incoming: mpsc::UnboundedSender<Action>,
+    mut outgoing: mpsc::UnboundedReceiver<Reaction>,
+) -> Result<(), Error> {
+    let listener = TcpListener::bind(config.address).await?;
+    log::info!("Remote control server listens on {}", config.address);
+    let mirror = Mirror::new();
+    let token = Arc::new(config.token);
+    loop {
+        select! {
+            reaction = outgoing.recv() => {
+                if let Some(reaction) = reaction {
+                    mirror.apply(reaction)?;
+                } else {
+                    // The bus has stopped
+                    break;
+                }
+            }
+            connection = listener.accept() => {
+                let (stream, peer) = match connection {
+                    Ok(connection) => connection,
+                    Err(err) => {
+                        // Running out of descriptors is temporary, so the server keeps serving
+                        log::error!("Can't accept a remote client: {}", err);
+                        sleep(ACCEPT_RETRY_DELAY).await;
+                        continue;
+                    },
+                };
+                let client = Client {
+                    peer,
+                    token: token.clone(),
+                    subscription: mirror.subscribe(incoming.clone())?,
+                };
+                tokio::spawn(async move {
+                    if let Err(err) = client.run(stream).await {
+                        log::warn!("Remote client {} failed: {}", peer, err);
+                    }
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Client {
+    peer: SocketAddr,
+    token: Arc<Hidden<String>>,
+    subscription: Subscription,
+}
+
+impl Client {
+    async fn run(self, stream: TcpStream) -> Result<(), Error> {
+        let token = self.token.clone();
+        let ws = accept_hdr_async(stream, |request: &Request, response: Response| {
+            if is_authorized(request, token.reveal()) {
+                Ok(response)
+            } else {
+                let mut error = ErrorResponse::new(Some("Unauthorized".into()));
+                *error.status_mut() = StatusCode::UNAUTHORIZED;
+                Err(error)
+            }
+        })
+        .await?;
+        log::info!("Remote client {} connected", self.peer);
+        let (mut sink, mut stream) = ws.split();
+        let Subscription {
+            state,
+            mut reactions,
+            mut replies,
+            actions,
+        } = self.subscription;
+        let snapshot = serde_json::to_string(&Reaction::State(state))?;
+        sink.send(Message::Text(snapshot)).await?;
+        loop {
+            select! {
+                Some(reply) = replies.recv() => {
+                    let text = serde_json::to_string(&reply)?;
+                    sink.send(Message::Text(text)).await?;
+                }
+                reaction = reactions.recv() => {
+                    match reaction {
+                        Ok(reaction) => {
+                            let text = serde_json::to_string(&reaction)?;
+                            sink.send(Message::Text(text)).await?;
+                        },
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            // The client has to reconnect to get the actual state
+                            return Err(anyhow!("The client missed {} reactions", skipped));
+                        },
+                        Err(broadcast::error::RecvError::Closed) => {
+                            break;
+                        },
+                    }
+                }
+                message = stream.next() => {
+                    match message {
+                        Some(Ok(Message::Text(text))) => {
+                            match serde_json::from_str::<Action>(&text) {
+                                Ok(action) => {
+                                    log::trace!("Remote action from {}: {:?}", self.peer, action);
+                                    actions.send(action)?;
+                                },
+                                Err(err) => {
+                                    log::error!("Can't parse a remote action from {}: {}", self.peer, err);
+                                },
+                            }
+                        },
+                        Some(Ok(Message::Close(_))) | None => {
+                            break;
+                        },
+                        Some(Ok(_)) => {},
+                        Some(Err(err)) => {
+                            return Err(err.into());
+                        },
+                    }
+                }
+            }
+        }
+        log::info!("Remote client {} disconnected", self.peer);
+        Ok(())
+    }
+}
+
+/// Checks the bearer token of the header or the `token` parameter of the query.
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let header = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let query = request
+        .uri()
+        .query()
+        .and_then(|query| query.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    header
+        .into_iter()
+        .chain(query)
+        .any(|value| constant_time_eq(value.as_bytes(), token.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -21,17 +21,18 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::{ops::Deref, path::PathBuf};
+use std::{collections::BTreeMap, net::IpAddr, path::PathBuf};
 
 use anyhow::Error;
 use serde::Serialize;
 use tari_base_node_grpc_client::grpc::NodeIdentity;
 use tari_common_types::{emoji::EmojiId, types::PublicKey};
-use tari_launchpad_protocol::session::LaunchpadSession;
 pub use tari_launchpad_protocol::{
     config::LaunchpadConfig,
-    settings::{LaunchpadSettings, TariNetwork, WalletConfig},
+    settings::{ImageSettings, LaunchpadSettings, Sha3MinerConfig, TariNetwork, WalletConfig},
+    wallet::WalletRestore,
 };
+use tari_launchpad_protocol::{mining::MiningDelta, session::LaunchpadSession};
 use tari_sdm::{config::ManagedProtocol, image::Envs};
 use tari_utilities::{ByteArray, Hidden};
 use tari_wallet_grpc_client::grpc::GetIdentityResponse;
@@ -42,7 +43,7 @@ pub struct LaunchpadProtocol;
 impl ManagedProtocol for LaunchpadProtocol {
     type Config = LaunchpadConfig;
     type Inner = LaunchpadInnerEvent;
-    type Outer = ();
+    type Outer = LaunchpadOuterEvent;
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +52,12 @@ pub enum LaunchpadInnerEvent {
     WalletIdentityReady(WalletIdentity),
 }
 
+/// Details reported by the tasks to the bus.
+#[derive(Debug, Clone)]
+pub enum LaunchpadOuterEvent {
+    MiningDelta(MiningDelta),
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BaseNodeIdentity {
@@ -104,9 +111,16 @@ impl TryFrom<GetIdentityResponse> for WalletIdentity {
 #[derive(Debug)]
 pub struct ConnectionSettings {
     pub session: LaunchpadSession,
-    pub tor_password: Hidden<String>,
+    /// It's not set until the secret store is unlocked.
+    pub tor_password: Option<Hidden<String>>,
     pub tari_network: TariNetwork,
     pub data_directory: PathBuf,
+    pub registry: Option<String>,
+    pub tag: Option<String>,
+    pub images: BTreeMap<String, ImageSettings>,
+    pub with_tor: bool,
+    pub public_address: Option<String>,
+    pub with_monitoring: bool,
 }
 
 impl ConnectionSettings {
@@ -114,19 +128,66 @@ impl ConnectionSettings {
         let settings = config.settings.as_ref()?;
         Some(ConnectionSettings {
             session: config.session.clone(),
-            tor_password: settings.tor_control_password.clone(),
+            tor_password: settings.tor_control_password.value.clone(),
             tari_network: settings.tari_network,
             data_directory: settings.data_directory.clone(),
+            registry: settings.registry.clone(),
+            tag: settings.tag.clone(),
+            images: settings.images.clone(),
+            with_tor: settings.with_tor,
+            public_address: settings.public_address.clone(),
+            with_monitoring: settings.with_monitoring,
         })
     }
+
+    /// Returns the overridden registry of the image.
+    /// The global value is used if `global` is set.
+    pub fn registry(&self, image: &str, global: bool) -> Option<&str> {
+        self.images
+            .get(image)
+            .and_then(|settings| settings.registry.as_deref())
+            .or_else(|| self.registry.as_deref().filter(|_| global))
+    }
+
+    /// Returns the overridden tag of the image.
+    /// The global value is used if `global` is set.
+    pub fn tag(&self, image: &str, global: bool) -> Option<&str> {
+        self.images
+            .get(image)
+            .and_then(|settings| settings.tag.as_deref())
+            .or_else(|| self.tag.as_deref().filter(|_| global))
+    }
 }
 
 impl ConnectionSettings {
+    /// Adds the password of the Tor control port if Tor is used.
     pub fn add_tor(&self, envs: &mut Envs) {
-        let value = format!("password={}", self.tor_password.deref());
+        if !self.with_tor {
+            return;
+        }
+        let password = self.tor_password.as_ref().map(|password| password.reveal().as_str());
+        let value = format!("password={}", password.unwrap_or_default());
         envs.set("TARI_BASE_NODE__P2P__TRANSPORT__TOR__CONTROL_AUTH", value);
     }
 
+    /// Sets the p2p transport of the application. Without Tor the application
+    /// listens TCP connections and announces the public address of the host
+    /// or the address inside the docker network.
+    pub fn add_transport(&self, envs: &mut Envs, app: &str, hostname: &str, port: u16) {
+        if self.with_tor {
+            self.add_tor(envs);
+        } else {
+            let prefix = format!("TARI_{}__P2P", app);
+            envs.set(&format!("{}__TRANSPORT__TYPE", prefix), "tcp");
+            envs.set(
+                &format!("{}__TRANSPORT__TCP__LISTENER_ADDRESS", prefix),
+                format!("/ip4/0.0.0.0/tcp/{}", port),
+            );
+            let host = self.public_address.as_deref().unwrap_or(hostname);
+            envs.set(&format!("{}__PUBLIC_ADDRESS", prefix), host_multiaddr(host, port));
+        }
+    }
+
     pub fn add_common(&self, envs: &mut Envs) {
         envs.set("TARI_NETWORK", self.tari_network.lower_case());
         envs.set("DATA_FOLDER", self.data_directory.to_str().unwrap_or(""));
@@ -142,3 +203,79 @@ impl ConnectionSettings {
         envs.set("PATH", path);
     }
 }
+
+pub fn host_multiaddr(host: &str, port: u16) -> String {
+    let protocol = match host.parse() {
+        Ok(IpAddr::V4(_)) => "ip4",
+        Ok(IpAddr::V6(_)) => "ip6",
+        Err(_) => "dns4",
+    };
+    format!("/{}/{}/tcp/{}", protocol, host, port)
+}
+
+#[cfg(test)]
+mod test {
+    use tari_sdm::{ids::ManagedTask, image::ManagedContainer};
+
+    use super::*;
+    use crate::resources::images::{TariBaseNode, Tor};
+
+    fn config(with_tor: bool, public_address: Option<&str>) -> LaunchpadConfig {
+        let settings = LaunchpadSettings {
+            with_tor,
+            public_address: public_address.map(String::from),
+            ..Default::default()
+        };
+        LaunchpadConfig {
+            settings: Some(settings),
+            ..Default::default()
+        }
+    }
+
+    fn transport_envs(with_tor: bool, public_address: Option<&str>) -> Vec<String> {
+        let settings = ConnectionSettings::try_extract(&config(with_tor, public_address)).unwrap();
+        let mut envs = Envs::default();
+        settings.add_transport(&mut envs, "WALLET", "wallet", 18_188);
+        envs.build()
+    }
+
+    #[test]
+    fn test_host_multiaddr() {
+        assert_eq!(host_multiaddr("203.0.113.7", 18_189), "/ip4/203.0.113.7/tcp/18189");
+        assert_eq!(host_multiaddr("2001:db8::1", 18_189), "/ip6/2001:db8::1/tcp/18189");
+        assert_eq!(
+            host_multiaddr("node.example.com", 18_189),
+            "/dns4/node.example.com/tcp/18189"
+        );
+    }
+
+    #[test]
+    fn test_tcp_transport() {
+        let envs = transport_envs(false, Some("203.0.113.7"));
+        assert!(envs.contains(&"TARI_WALLET__P2P__TRANSPORT__TYPE=tcp".to_string()));
+        assert!(envs.contains(&"TARI_WALLET__P2P__TRANSPORT__TCP__LISTENER_ADDRESS=/ip4/0.0.0.0/tcp/18188".to_string()));
+        assert!(envs.contains(&"TARI_WALLET__P2P__PUBLIC_ADDRESS=/ip4/203.0.113.7/tcp/18188".to_string()));
+
+        // The address inside the docker network is used by default
+        let envs = transport_envs(false, None);
+        assert!(envs.contains(&"TARI_WALLET__P2P__PUBLIC_ADDRESS=/dns4/wallet/tcp/18188".to_string()));
+    }
+
+    #[test]
+    fn test_tor_transport() {
+        let envs = transport_envs(true, Some("203.0.113.7"));
+        assert!(envs.iter().all(|env| !env.starts_with("TARI_WALLET__P2P")));
+        assert!(envs
+            .iter()
+            .any(|env| env.starts_with("TARI_BASE_NODE__P2P__TRANSPORT__TOR__CONTROL_AUTH=")));
+    }
+
+    #[test]
+    fn test_skipped_deps() {
+        let mut base_node = TariBaseNode::default();
+        base_node.reconfigure(Some(&config(true, None)));
+        assert!(base_node.skipped_deps().is_empty());
+        base_node.reconfigure(Some(&config(false, None)));
+        assert_eq!(base_node.skipped_deps(), vec![Tor::id()]);
+    }
+}
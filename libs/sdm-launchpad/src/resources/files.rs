@@ -21,10 +21,24 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Error;
-use tokio::fs;
+use anyhow::{anyhow, Error};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tari_launchpad_protocol::settings::{
+    LaunchpadSettings,
+    WalletConfig,
+    MONERO_PASSWORD,
+    TOR_CONTROL_PASSWORD,
+    WALLET_PASSWORD,
+};
+use tari_utilities::Hidden;
+use tokio::{fs, io::AsyncWriteExt};
 
 macro_rules! embed_file {
     ($f:literal) => {
@@ -39,6 +53,23 @@ const LOKI_YML: ConfigFile = embed_file!("loki_config.yml");
 const PROMTAIL_YML: ConfigFile = embed_file!("promtail.config.yml");
 const PROVISION_YML: ConfigFile = embed_file!("sources_provision.yml");
 
+const SETTINGS_FILE: &str = "launchpad_settings.json";
+const HISTORY_FILE: &str = "transactions.jsonl";
+/// The wallet writes its seed words here on the first start.
+const SEED_WORDS_FILE: &str = "seed_words.txt";
+/// The seed words of a pending restore relative to the data directory.
+pub const RESTORE_WORDS_PATH: &str = "config/restore_words.txt";
+const SECRETS_FILE: &str = "secrets.json";
+/// The directory of the socket is private to the owner.
+const SOCKET_DIR: &str = "daemon";
+const SOCKET_FILE: &str = "launchpad.sock";
+
+/// The version of the settings file format.
+/// Increment it and add a step to `migrate_settings`
+/// every time the format of the `LaunchpadSettings` changed
+/// in an incompatible way.
+const SETTINGS_VERSION: u64 = 2;
+
 struct ConfigFile {
     filename: &'static str,
     data: &'static str,
@@ -52,6 +83,9 @@ impl ConfigFile {
 
 pub struct Configurator {
     base_dir: PathBuf,
+    /// The plain text passwords of the old settings files
+    /// that wait to be moved to the secret store.
+    legacy_secrets: BTreeMap<String, Hidden<String>>,
 }
 
 impl Configurator {
@@ -59,23 +93,196 @@ impl Configurator {
         let cache_dir = dirs_next::cache_dir().ok_or_else(|| Error::msg("No cache dir"))?;
         let mut data_directory = cache_dir;
         data_directory.push("tari-launchpad");
-        Ok(Self {
-            base_dir: data_directory,
-        })
+        Ok(Self::with_base_dir(data_directory))
+    }
+
+    pub fn with_base_dir(base_dir: PathBuf) -> Self {
+        Self {
+            base_dir,
+            legacy_secrets: BTreeMap::new(),
+        }
     }
 
     pub fn base_path(&self) -> &PathBuf {
         &self.base_dir
     }
 
-    // pub async fn read_config(&self) -> Result<LaunchpadConfig, Error> {
-    // let mut path = self.base_dir.clone();
-    // path.push("config");
-    // path.push("config.toml");
-    // let data = fs::read_to_string(&path).await?;
-    // let config = toml::from_str(&data)?;
-    // Ok(config)
-    // }
+    fn settings_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push("config");
+        path.push(SETTINGS_FILE);
+        path
+    }
+
+    /// The local history of the wallet transactions.
+    pub fn history_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push("history");
+        path.push(HISTORY_FILE);
+        path
+    }
+
+    /// The files exported on requests of the clients.
+    pub fn exports_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push("exports");
+        path
+    }
+
+    /// The control socket of the launchpad daemon.
+    pub fn socket_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push(SOCKET_DIR);
+        path.push(SOCKET_FILE);
+        path
+    }
+
+    /// The encrypted store of the passwords.
+    pub fn secrets_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push("config");
+        path.push(SECRETS_FILE);
+        path
+    }
+
+    /// Takes the passwords that were stored in the settings
+    /// before the secret store. They are kept in the settings
+    /// file until the secret store is unlocked.
+    pub fn take_legacy_secrets(&mut self) -> BTreeMap<String, Hidden<String>> {
+        std::mem::take(&mut self.legacy_secrets)
+    }
+
+    /// Reads the settings from the disk or creates the default
+    /// settings if the settings file doesn't exist yet.
+    pub async fn read_settings(&mut self) -> Result<LaunchpadSettings, Error> {
+        let path = self.settings_path();
+        let mut settings = if path.exists() {
+            let data = fs::read_to_string(&path).await?;
+            let value = serde_json::from_str(&data)?;
+            let value = migrate_settings(value)?;
+            let file: SettingsFile = serde_json::from_value(value)?;
+            self.legacy_secrets = file.legacy_secrets;
+            file.settings
+        } else {
+            log::info!("Settings file {} doesn't exist. Using defaults.", path.display());
+            default_settings()
+        };
+        // The data directory is always managed by the configurator
+        settings.data_directory = self.base_dir.clone();
+        Ok(settings)
+    }
+
+    /// Stores the settings to the disk. It writes a temporary file
+    /// first and renames it to avoid broken settings if the app crashed.
+    pub async fn write_settings(&self, settings: &LaunchpadSettings) -> Result<(), Error> {
+        let path = self.settings_path();
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            settings: settings.clone(),
+            legacy_secrets: self.legacy_secrets.clone(),
+        };
+        let data = serde_json::to_string_pretty(&file)?;
+        let tmp_path = path.with_extension("json.tmp");
+        if tmp_path.exists() {
+            // A leftover of a crash could be readable by others
+            fs::remove_file(&tmp_path).await?;
+        }
+        // The legacy passwords are kept here, so only the owner can read the file
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&tmp_path).await?;
+        file.write_all(data.as_bytes()).await?;
+        file.sync_all().await?;
+        drop(file);
+        fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    fn seed_words_path(&self) -> PathBuf {
+        let mut path = self.base_dir.clone();
+        path.push("config");
+        path.push(SEED_WORDS_FILE);
+        path
+    }
+
+    /// The wallet directory has the data of a created wallet.
+    pub fn has_wallet(&self) -> bool {
+        std::fs::read_dir(self.base_dir.join("wallet"))
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or_default()
+    }
+
+    pub fn has_seed_words(&self) -> bool {
+        self.seed_words_path().exists()
+    }
+
+    pub async fn read_seed_words(&self) -> Result<Vec<String>, Error> {
+        let data = fs::read_to_string(self.seed_words_path()).await?;
+        Ok(data.split_whitespace().map(String::from).collect())
+    }
+
+    pub async fn delete_seed_words(&self) -> Result<(), Error> {
+        wipe_file(&self.seed_words_path()).await
+    }
+
+    /// The seed words of a pending restore. The file is a marker of the restore as well:
+    /// it's kept until the recovered wallet is ready, so the restore survives restarts.
+    fn restore_words_path(&self) -> PathBuf {
+        self.base_dir.join(RESTORE_WORDS_PATH)
+    }
+
+    pub fn has_pending_restore(&self) -> bool {
+        self.restore_words_path().exists()
+    }
+
+    /// Writes the seed words for the wallet container that reads them
+    /// from the bind-mounted config folder. Only the owner can read the file.
+    pub async fn write_restore_words(&self, words: &[String]) -> Result<(), Error> {
+        let path = self.restore_words_path();
+        if path.exists() {
+            wipe_file(&path).await?;
+        }
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options.open(&path).await?;
+        // The wallet reads the words as a line of its prompt
+        let line = format!("{}\n", words.join(" "));
+        file.write_all(line.as_bytes()).await?;
+        file.sync_all().await?;
+        Ok(())
+    }
+
+    pub async fn read_restore_words(&self) -> Result<Vec<String>, Error> {
+        let data = fs::read_to_string(self.restore_words_path()).await?;
+        Ok(data.split_whitespace().map(String::from).collect())
+    }
+
+    pub async fn delete_restore_words(&self) -> Result<(), Error> {
+        wipe_file(&self.restore_words_path()).await
+    }
+
+    /// Moves the data and the seed words of the current wallet
+    /// to a backup folder and returns the path to the folder.
+    pub async fn archive_wallet(&self) -> Result<PathBuf, Error> {
+        let wallet_dir = self.base_dir.join("wallet");
+        let name = format!("wallet.{}.bak", Local::now().format("%Y%m%d%H%M%S"));
+        let backup_dir = self.base_dir.join(name);
+        if wallet_dir.exists() {
+            fs::rename(&wallet_dir, &backup_dir).await?;
+        } else {
+            fs::create_dir_all(&backup_dir).await?;
+        }
+        let seed_words = self.seed_words_path();
+        if seed_words.exists() {
+            fs::rename(&seed_words, backup_dir.join(SEED_WORDS_FILE)).await?;
+        }
+        fs::create_dir_all(&wallet_dir).await?;
+        Ok(backup_dir)
+    }
 
     async fn create_dir(&mut self, folder: &Path) -> Result<(), Error> {
         if !folder.exists() {
@@ -128,3 +335,185 @@ impl Configurator {
         Ok(())
     }
 }
+
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    version: u64,
+    settings: LaunchpadSettings,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    legacy_secrets: BTreeMap<String, Hidden<String>>,
+}
+
+fn default_settings() -> LaunchpadSettings {
+    LaunchpadSettings {
+        with_monitoring: true,
+        wallet: Some(WalletConfig::default()),
+        ..Default::default()
+    }
+}
+
+/// Upgrades the raw settings to the `SETTINGS_VERSION` step by step.
+fn migrate_settings(mut value: Value) -> Result<Value, Error> {
+    let mut version = value.get("version").and_then(Value::as_u64).unwrap_or_default();
+    if version > SETTINGS_VERSION {
+        return Err(anyhow!(
+            "Settings version {} is newer than supported version {}",
+            version,
+            SETTINGS_VERSION
+        ));
+    }
+    while version < SETTINGS_VERSION {
+        log::info!("Migrating settings from version {}", version);
+        value = match version {
+            0 => migrate_v0(value),
+            1 => migrate_v1(value),
+            other => return Err(anyhow!("No migration for the settings version {}", other)),
+        };
+        version += 1;
+    }
+    Ok(value)
+}
+
+/// The version `0` is an unversioned plain `LaunchpadSettings` object.
+fn migrate_v0(value: Value) -> Value {
+    serde_json::json!({
+        "version": 1,
+        "settings": value,
+    })
+}
+
+/// The version `1` keeps the passwords in plain text. They are replaced
+/// with references to the secret store and kept aside to be imported
+/// to the store when it's unlocked.
+fn migrate_v1(mut value: Value) -> Value {
+    let mut legacy = Map::new();
+    if let Some(settings) = value.get_mut("settings").filter(|settings| settings.is_object()) {
+        move_secret(settings, &["tor_control_password"], TOR_CONTROL_PASSWORD, &mut legacy);
+        move_secret(settings, &["wallet", "password"], WALLET_PASSWORD, &mut legacy);
+        move_secret(settings, &["mm_proxy", "monero_password"], MONERO_PASSWORD, &mut legacy);
+    }
+    value["version"] = 2.into();
+    value["legacy_secrets"] = Value::Object(legacy);
+    value
+}
+
+fn move_secret(settings: &mut Value, path: &[&str], name: &str, legacy: &mut Map<String, Value>) {
+    let (field, parents) = match path.split_last() {
+        Some(pair) => pair,
+        None => return,
+    };
+    let mut target = settings;
+    for parent in parents {
+        target = match target.get_mut(*parent) {
+            Some(value) if value.is_object() => value,
+            _ => return,
+        };
+    }
+    if let Some(Value::String(password)) = target.get(*field) {
+        legacy.insert(name.to_string(), Value::String(password.clone()));
+    }
+    target[*field] = serde_json::json!({ "name": name });
+}
+
+/// Overwrites the file with zeros before removing it
+/// to not leave the seed words in the free blocks of the disk.
+async fn wipe_file(path: &Path) -> Result<(), Error> {
+    let len = fs::metadata(path).await?.len();
+    let mut file = fs::OpenOptions::new().write(true).open(path).await?;
+    file.write_all(&vec![0; len as usize]).await?;
+    file.sync_all().await?;
+    drop(file);
+    fs::remove_file(path).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, process};
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn legacy_settings() -> Value {
+        json!({
+            "tari_network": "Esmeralda",
+            "tor_control_password": "tor secret",
+            "wallet": { "password": "wallet secret" },
+            "with_tor": false,
+        })
+    }
+
+    fn read_file(value: Value) -> Result<SettingsFile, Error> {
+        let value = migrate_settings(value)?;
+        assert_eq!(value["version"], json!(SETTINGS_VERSION));
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn legacy_secret(file: &SettingsFile, name: &str) -> Option<String> {
+        file.legacy_secrets.get(name).map(|value| value.reveal().clone())
+    }
+
+    #[test]
+    fn test_migrate_v0() -> Result<(), Error> {
+        let file = read_file(legacy_settings())?;
+        assert!(!file.settings.with_tor);
+        assert_eq!(file.settings.tor_control_password.name, TOR_CONTROL_PASSWORD);
+        assert_eq!(
+            legacy_secret(&file, TOR_CONTROL_PASSWORD).as_deref(),
+            Some("tor secret")
+        );
+        assert_eq!(legacy_secret(&file, WALLET_PASSWORD).as_deref(), Some("wallet secret"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_v1() -> Result<(), Error> {
+        let mut settings = legacy_settings();
+        settings["mm_proxy"] = json!({
+            "monerod_url": "http://localhost:18081",
+            "monero_username": "user",
+            "monero_password": "monero secret",
+            "monero_use_auth": true,
+        });
+        let file = read_file(json!({ "version": 1, "settings": settings }))?;
+        let wallet = file.settings.wallet.as_ref().expect("no wallet settings");
+        assert_eq!(wallet.password.name, WALLET_PASSWORD);
+        let mm_proxy = file.settings.mm_proxy.as_ref().expect("no mm proxy settings");
+        assert_eq!(mm_proxy.monero_password.name, MONERO_PASSWORD);
+        assert_eq!(legacy_secret(&file, MONERO_PASSWORD).as_deref(), Some("monero secret"));
+        assert_eq!(file.legacy_secrets.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_version_rejected() {
+        let value = json!({ "version": SETTINGS_VERSION + 1, "settings": {} });
+        assert!(migrate_settings(value).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_settings_round_trip() -> Result<(), Error> {
+        let mut base_dir = env::temp_dir();
+        base_dir.push(format!("tari-launchpad-settings-{}", process::id()));
+        fs::create_dir_all(base_dir.join("config")).await?;
+        let mut configurator = Configurator::with_base_dir(base_dir.clone());
+        let settings = LaunchpadSettings {
+            data_directory: base_dir.clone(),
+            with_tor: false,
+            public_address: Some("node.example.com".into()),
+            ..default_settings()
+        };
+        configurator.write_settings(&settings).await?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(configurator.settings_path()).await?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let restored = configurator.read_settings().await?;
+        assert_eq!(serde_json::to_value(&restored)?, serde_json::to_value(&settings)?);
+        fs::remove_dir_all(&base_dir).await?;
+        Ok(())
+    }
+}
@@ -21,8 +21,6 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::ops::Deref;
-
 use async_trait::async_trait;
 use regex::Regex;
 use tari_launchpad_protocol::container::TaskProgress;
@@ -34,11 +32,13 @@ use tari_sdm::{
         Envs,
         ManagedContainer,
         Networks,
+        Ports,
     },
 };
+use tari_utilities::Hidden;
 use tor_hash_passwd::EncryptedKey;
 
-use super::DEFAULT_REGISTRY;
+use super::{Origin, Runtime, TOR_CONTROL_PORT, TOR_SOCKS_PORT};
 use crate::resources::{
     config::{ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
     networks::LocalNet,
@@ -46,7 +46,12 @@ use crate::resources::{
 
 #[derive(Debug, Default)]
 pub struct Tor {
+    /// Where the applications that connect to Tor run
+    runtime: Runtime,
     settings: Option<ConnectionSettings>,
+    /// The hash is salted randomly. It's kept with the password
+    /// to not recreate the container until the password is changed.
+    hashed_password: Option<(Hidden<String>, String)>,
 }
 
 impl ManagedTask for Tor {
@@ -59,21 +64,46 @@ impl ManagedTask for Tor {
     }
 }
 
+impl Tor {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            ..Self::default()
+        }
+    }
+}
+
 impl ManagedContainer for Tor {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "tor"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         self.settings = ConnectionSettings::try_extract(config?);
-        let session = &self.settings.as_ref()?.session;
-        Some(session.all_active || session.base_layer_active || session.tor_active)
+        let settings = self.settings.as_ref()?;
+        // Waits for the unlocked secret store
+        let password = settings.tor_password.as_ref()?;
+        let changed = self
+            .hashed_password
+            .as_ref()
+            .map(|(known, _)| known.reveal() != password.reveal())
+            .unwrap_or(true);
+        if changed {
+            let hashed = EncryptedKey::hash_password(password.reveal()).to_string();
+            self.hashed_password = Some((password.clone(), hashed));
+        }
+        let session = &settings.session;
+        Some(settings.with_tor && (session.all_active || session.base_layer_active || session.tor_active))
     }
 
     fn checker(&mut self) -> Box<dyn ContainerChecker<LaunchpadProtocol>> {
@@ -81,13 +111,13 @@ impl ManagedContainer for Tor {
     }
 
     fn args(&self, args: &mut Args) {
-        args.set_pair("--SocksPort", "0.0.0.0:9050");
-        args.set_pair("--ControlPort", "0.0.0.0:9051");
+        args.set_pair("--SocksPort", format!("0.0.0.0:{}", TOR_SOCKS_PORT));
+        args.set_pair("--ControlPort", format!("0.0.0.0:{}", TOR_CONTROL_PORT));
         args.set_pair("--CookieAuthentication", 0);
         args.set_pair("--ClientOnly", 1);
         args.set_pair("--ClientUseIPv6", 1);
-        if let Some(settings) = self.settings.as_ref() {
-            let hashed = EncryptedKey::hash_password(settings.tor_password.deref());
+        // Only the hash is shared with the container
+        if let Some((_, hashed)) = self.hashed_password.as_ref() {
             args.set_pair("--HashedControlPassword", hashed);
         }
         args.flag("--allow-missing-torrc");
@@ -99,6 +129,15 @@ impl ManagedContainer for Tor {
         }
     }
 
+    fn ports(&self, ports: &mut Ports) {
+        // Processes of the host reach Tor by the published ports.
+        // The proxy is not published for other hosts.
+        if self.runtime == Runtime::Native {
+            ports.add_local(TOR_SOCKS_PORT);
+            ports.add_local(TOR_CONTROL_PORT);
+        }
+    }
+
     fn networks(&self, networks: &mut Networks) {
         networks.add("tor", LocalNet::id());
     }
@@ -21,6 +21,8 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use std::{path::PathBuf, time::Duration};
+
 use anyhow::Error;
 use async_trait::async_trait;
 use tari_base_node_grpc_client::{grpc, BaseNodeGrpcClient};
@@ -35,27 +37,33 @@ use tari_sdm::{
         Mounts,
         Networks,
         Ports,
+        Timeouts,
         Volumes,
     },
+    process::ManagedProcess,
 };
 
 use super::{
+    shared_volume_dir,
     sync_progress::SyncProgress,
+    Origin,
+    Runtime,
     Tor,
     BLOCKCHAIN_PATH,
     BLOCKCHAIN_VOLUME,
-    DEFAULT_REGISTRY,
     GENERAL_VOLUME,
+    LOCALHOST,
     VAR_TARI_PATH,
 };
 use crate::resources::{
-    config::{ConnectionSettings, LaunchpadConfig, LaunchpadInnerEvent, LaunchpadProtocol},
+    config::{host_multiaddr, ConnectionSettings, LaunchpadConfig, LaunchpadInnerEvent, LaunchpadProtocol},
     networks::LocalNet,
     volumes::SharedVolume,
 };
 
 #[derive(Debug, Default)]
 pub struct TariBaseNode {
+    runtime: Runtime,
     settings: Option<ConnectionSettings>,
 }
 
@@ -69,28 +77,63 @@ impl ManagedTask for TariBaseNode {
     }
 }
 
+impl TariBaseNode {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            ..Self::default()
+        }
+    }
+
+    fn with_tor(&self) -> bool {
+        self.settings.as_ref().map(|settings| settings.with_tor).unwrap_or(true)
+    }
+}
+
 impl ManagedContainer for TariBaseNode {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "tari_base_node"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        // The database has to be flushed before the exit
+        Timeouts {
+            stop: Duration::from_secs(60),
+            killed: Duration::from_secs(90),
+            ..Timeouts::default()
+        }
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         self.settings = ConnectionSettings::try_extract(config?);
         let session = &self.settings.as_ref()?.session;
         Some(session.all_active || session.base_layer_active || session.base_node_active)
     }
 
+    fn skipped_deps(&self) -> Vec<TaskId> {
+        if self.with_tor() {
+            Vec::new()
+        } else {
+            vec![Tor::id()]
+        }
+    }
+
     fn checker(&mut self) -> Box<dyn ContainerChecker<LaunchpadProtocol>> {
         Box::new(Checker::new())
     }
 
     fn args(&self, args: &mut Args) {
+        self.runtime.add_paths(args, "base_node");
         args.set("--log-config", "/var/tari/config/log4rs.yml");
         args.flag("-n");
         args.set("--watch", "status");
@@ -99,7 +142,23 @@ impl ManagedContainer for TariBaseNode {
     fn envs(&self, envs: &mut Envs) {
         if let Some(settings) = self.settings.as_ref() {
             settings.add_common(envs);
-            settings.add_tor(envs);
+            settings.add_transport(envs, "BASE_NODE", self.runtime.host("base_node"), 18_189);
+            if !settings.with_tor {
+                // The port is taken by the TCP transport. The wallet
+                // reaches the listener inside the docker network only.
+                envs.set(
+                    "TARI_BASE_NODE__P2P__AUXILIARY_TCP_LISTENER_ADDRESS",
+                    "/ip4/0.0.0.0/tcp/18190",
+                );
+            }
+            if settings.with_tor && self.runtime == Runtime::Native {
+                // The listener of the config file uses the host of the docker network
+                envs.set(
+                    "TARI_BASE_NODE__P2P__AUXILIARY_TCP_LISTENER_ADDRESS",
+                    host_multiaddr(LOCALHOST, 18_189),
+                );
+            }
+            self.runtime.add_tor_addresses(envs, "BASE_NODE");
             // envs.set("WAIT_FOR_TOR", 10);
             envs.set(
                 "TARI_BASE_NODE__DATA_DIR",
@@ -132,6 +191,12 @@ impl ManagedContainer for TariBaseNode {
     }
 }
 
+impl ManagedProcess for TariBaseNode {
+    fn volume_dir(&self, volume: &TaskId) -> Option<PathBuf> {
+        shared_volume_dir(&self.settings, volume)
+    }
+}
+
 struct Checker {
     progress: SyncProgress,
     identity_sent: bool,
@@ -21,7 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::ops::Deref;
+use std::path::PathBuf;
 
 use anyhow::Error;
 use async_trait::async_trait;
@@ -37,11 +37,12 @@ use tari_sdm::{
         Ports,
         Volumes,
     },
+    process::ManagedProcess,
 };
 use tari_utilities::hex::Hex;
 use tari_wallet_grpc_client::{grpc::GetIdentityRequest, WalletGrpcClient};
 
-use super::{TariBaseNode, DEFAULT_REGISTRY, GENERAL_VOLUME};
+use super::{shared_volume_dir, Origin, Runtime, TariBaseNode, GENERAL_VOLUME};
 use crate::resources::{
     config::{
         BaseNodeIdentity,
@@ -50,7 +51,9 @@ use crate::resources::{
         LaunchpadInnerEvent,
         LaunchpadProtocol,
         WalletConfig,
+        WalletRestore,
     },
+    files::RESTORE_WORDS_PATH,
     images::{BLOCKCHAIN_PATH, VAR_TARI_PATH},
     networks::LocalNet,
     volumes::SharedVolume,
@@ -58,8 +61,10 @@ use crate::resources::{
 
 #[derive(Debug, Default)]
 pub struct TariWallet {
+    runtime: Runtime,
     settings: Option<ConnectionSettings>,
     wallet: Option<WalletConfig>,
+    restore: Option<WalletRestore>,
     identity: Option<BaseNodeIdentity>,
 }
 
@@ -73,23 +78,45 @@ impl ManagedTask for TariWallet {
     }
 }
 
+impl TariWallet {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            ..Self::default()
+        }
+    }
+}
+
 impl ManagedContainer for TariWallet {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "tari_wallet"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         let config = config?;
         self.settings = ConnectionSettings::try_extract(config);
         self.wallet = config.settings.as_ref().and_then(|s| s.wallet.clone());
-        let session = &self.settings.as_ref()?.session;
-        self.wallet.as_ref()?;
+        self.restore = config.restore.clone();
+        let settings = self.settings.as_ref()?;
+        // Waits for the unlocked secret store
+        self.wallet.as_ref()?.password.reveal()?;
+        // A new wallet would be created instead of the one that waits for the recovery
+        let restore_pending = settings.data_directory.join(RESTORE_WORDS_PATH).exists();
+        if restore_pending && self.restore.is_none() {
+            log::warn!("The wallet is not started until the pending restore is resumed");
+            return Some(false);
+        }
+        let session = &settings.session;
         Some(session.all_active || session.base_layer_active || session.wallet_active)
     }
 
@@ -112,11 +139,16 @@ impl ManagedContainer for TariWallet {
     }
 
     fn args(&self, args: &mut Args) {
+        self.runtime.add_paths(args, "wallet");
         args.set("--log-config", "/var/tari/config/log4rs.yml");
         args.set("--seed-words-file", "/var/tari/config/seed_words.txt");
         args.flag("--enable-grpc");
         args.flag("-n");
 
+        if self.restore.is_some() {
+            args.flag("--recovery");
+        }
+
         if let Some(identity) = self.identity.as_ref() {
             let value = format!(
                 "wallet.custom_base_node={}::{}",
@@ -130,16 +162,26 @@ impl ManagedContainer for TariWallet {
     fn envs(&self, envs: &mut Envs) {
         if let Some(settings) = self.settings.as_ref() {
             settings.add_common(envs);
-            settings.add_tor(envs);
+            settings.add_transport(envs, "WALLET", self.runtime.host("wallet"), 18_188);
+            self.runtime.add_tor_addresses(envs, "WALLET");
             envs.set("WAIT_FOR_TOR", 0);
             envs.set(
                 "TARI_BASE_NODE__DATA_DIR",
                 format!("/blockchain/{}", settings.tari_network.lower_case()),
             );
         }
-        if let Some(wallet) = self.wallet.as_ref() {
-            // TODO: Use `.reveal()` instead
-            envs.set("TARI_WALLET_PASSWORD", wallet.password.deref());
+        if let Some(restore) = self.restore.as_ref() {
+            // The start script reads the words, so they are not a part of the container spec
+            envs.set(
+                "RECOVERY_SEED_WORDS_FILE",
+                format!("{}/{}", VAR_TARI_PATH, RESTORE_WORDS_PATH),
+            );
+            if let Some(birthday) = restore.birthday {
+                envs.set("TARI_WALLET__RECOVERY_BIRTHDAY", birthday);
+            }
+        }
+        if let Some(password) = self.wallet.as_ref().and_then(|wallet| wallet.password.reveal()) {
+            envs.set("TARI_WALLET_PASSWORD", password);
         }
         envs.set("SHELL", "/bin/bash");
         envs.set("TERM", "linux");
@@ -164,6 +206,23 @@ impl ManagedContainer for TariWallet {
     }
 }
 
+impl ManagedProcess for TariWallet {
+    fn program(&self) -> PathBuf {
+        "tari_console_wallet".into()
+    }
+
+    fn volume_dir(&self, volume: &TaskId) -> Option<PathBuf> {
+        shared_volume_dir(&self.settings, volume)
+    }
+
+    fn stdin(&self) -> Option<String> {
+        // The wallet prompts for the seed words of the recovery
+        self.restore
+            .as_ref()
+            .map(|_| format!("{}/{}", VAR_TARI_PATH, RESTORE_WORDS_PATH))
+    }
+}
+
 struct Checker {
     identity_sent: bool,
 }
@@ -21,14 +21,24 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use std::path::PathBuf;
+
 use tari_sdm::{
     ids::{ManagedTask, TaskId},
-    image::{Args, Envs, ManagedContainer, Mounts, Networks, Volumes},
+    image::{checker::ContainerChecker, Args, Envs, ManagedContainer, Mounts, Networks, Volumes},
+    process::ManagedProcess,
 };
 
-use super::{TariBaseNode, TariWallet, DEFAULT_REGISTRY, GENERAL_VOLUME};
+use super::{
+    mining_stats::{MiningChecker, MiningStatsParser, SharedStats},
+    Origin,
+    Runtime,
+    TariBaseNode,
+    TariWallet,
+    GENERAL_VOLUME,
+};
 use crate::resources::{
-    config::{ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
+    config::{host_multiaddr, ConnectionSettings, LaunchpadConfig, LaunchpadProtocol, Sha3MinerConfig},
     images::VAR_TARI_PATH,
     networks::LocalNet,
     volumes::SharedVolume,
@@ -36,7 +46,10 @@ use crate::resources::{
 
 #[derive(Debug, Default)]
 pub struct TariSha3Miner {
+    runtime: Runtime,
     settings: Option<ConnectionSettings>,
+    sha3_miner: Sha3MinerConfig,
+    stats: SharedStats,
 }
 
 impl ManagedTask for TariSha3Miner {
@@ -49,24 +62,48 @@ impl ManagedTask for TariSha3Miner {
     }
 }
 
+impl TariSha3Miner {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            ..Self::default()
+        }
+    }
+}
+
 impl ManagedContainer for TariSha3Miner {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "tari_sha3_miner"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
-        self.settings = ConnectionSettings::try_extract(config?);
+        let config = config?;
+        self.settings = ConnectionSettings::try_extract(config);
+        self.sha3_miner = config
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.sha3_miner.clone())
+            .unwrap_or_default();
         let session = &self.settings.as_ref()?.session;
         Some(session.all_active || session.base_layer_active || session.miner_active)
     }
 
+    fn checker(&mut self) -> Box<dyn ContainerChecker<LaunchpadProtocol>> {
+        Box::new(MiningChecker::new(MiningStatsParser::sha3(), &self.stats))
+    }
+
     fn args(&self, args: &mut Args) {
+        self.runtime.add_paths(args, "sha3_miner");
         args.set("--log-config", "/var/tari/config/log4rs.yml");
     }
 
@@ -74,16 +111,22 @@ impl ManagedContainer for TariSha3Miner {
         if let Some(settings) = self.settings.as_ref() {
             settings.add_common(envs);
             settings.add_tor(envs);
-            envs.set("TARI_MINER__NUM_MINING_THREADS", 8); // TODO: Get config num
-            envs.set("TARI_MINER__MINE_ON_TIP_ONLY", 1);
+            envs.set("TARI_MINER__NUM_MINING_THREADS", self.sha3_miner.mining_threads());
+            envs.set("TARI_MINER__MINE_ON_TIP_ONLY", self.sha3_miner.mine_on_tip_only);
+            if !self.sha3_miner.coinbase_extra.is_empty() {
+                envs.set("TARI_MINER__COINBASE_EXTRA", &self.sha3_miner.coinbase_extra);
+            }
             envs.set(
                 &format!(
                     "TARI_BASE_NODE__{}__GRPC_BASE_NODE_GRPC_ADDRESS",
                     settings.tari_network.upper_case()
                 ),
-                "/dns4/base_node/tcp/18142",
+                host_multiaddr(self.runtime.host("base_node"), 18_142),
+            );
+            envs.set(
+                "TARI_WALLET__GRPC_ADDRESS",
+                host_multiaddr(self.runtime.host("wallet"), 18_143),
             );
-            envs.set("TARI_WALLET__GRPC_ADDRESS", "/dns4/wallet/tcp/18143");
         }
         envs.set("SHELL", "/bin/bash");
         envs.set("TERM", "linux");
@@ -105,3 +148,9 @@ impl ManagedContainer for TariSha3Miner {
         }
     }
 }
+
+impl ManagedProcess for TariSha3Miner {
+    fn program(&self) -> PathBuf {
+        "tari_miner".into()
+    }
+}
@@ -21,22 +21,24 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::ops::Deref;
+use std::path::PathBuf;
 
 use tari_launchpad_protocol::settings::MmProxyConfig;
 use tari_sdm::{
     ids::{ManagedTask, TaskId},
-    image::{Args, Envs, ManagedContainer, Networks},
+    image::{Args, Envs, ManagedContainer, Mounts, Networks},
+    process::ManagedProcess,
 };
 
-use super::DEFAULT_REGISTRY;
+use super::{Origin, Runtime, VAR_TARI_PATH};
 use crate::resources::{
-    config::{ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
+    config::{host_multiaddr, ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
     networks::LocalNet,
 };
 
 #[derive(Debug, Default)]
 pub struct MmProxy {
+    runtime: Runtime,
     settings: Option<ConnectionSettings>,
     mm_proxy: Option<MmProxyConfig>,
 }
@@ -51,26 +53,44 @@ impl ManagedTask for MmProxy {
     }
 }
 
+impl MmProxy {
+    pub fn new(runtime: Runtime) -> Self {
+        Self {
+            runtime,
+            ..Self::default()
+        }
+    }
+}
+
 impl ManagedContainer for MmProxy {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "tari_mm_proxy"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         self.settings = ConnectionSettings::try_extract(config?);
         let session = &self.settings.as_ref()?.session;
         self.mm_proxy = config?.settings.as_ref()?.mm_proxy.clone();
-        self.mm_proxy.as_ref()?; // To check it exists
+        let mm_proxy = self.mm_proxy.as_ref()?;
+        if mm_proxy.monero_use_auth {
+            // Waits for the unlocked secret store
+            mm_proxy.monero_password.reveal()?;
+        }
         Some(session.all_active || session.merge_layer_active || session.mmproxy_active)
     }
 
     fn args(&self, args: &mut Args) {
+        self.runtime.add_paths(args, "mm_proxy");
         args.set("--log-config", "/var/tari/config/log4rs.yml");
     }
 
@@ -78,6 +98,21 @@ impl ManagedContainer for MmProxy {
         if let Some(settings) = self.settings.as_ref() {
             settings.add_tor(envs);
         }
+        if self.runtime == Runtime::Native {
+            // The config file has the hosts of the docker network
+            envs.set(
+                "TARI_MERGE_MINING_PROXY__BASE_NODE_GRPC_ADDRESS",
+                host_multiaddr(self.runtime.host("base_node"), 18_142),
+            );
+            envs.set(
+                "TARI_MERGE_MINING_PROXY__CONSOLE_WALLET_GRPC_ADDRESS",
+                host_multiaddr(self.runtime.host("wallet"), 18_143),
+            );
+            envs.set(
+                "TARI_MERGE_MINING_PROXY__LISTENER_ADDRESS",
+                host_multiaddr(self.runtime.host("mm_proxy"), 18_081),
+            );
+        }
         envs.set("APP_NAME", "mm_proxy");
         envs.set("APP_EXEC", "tari_merge_mining_proxy");
         if let Some(config) = self.mm_proxy.as_ref() {
@@ -85,7 +120,7 @@ impl ManagedContainer for MmProxy {
             envs.set("TARI_MERGE_MINING_PROXY__MONEROD_USERNAME", &config.monero_username);
             envs.set(
                 "TARI_MERGE_MINING_PROXY__MONEROD_PASSWORD",
-                config.monero_password.deref(),
+                config.monero_password.reveal().unwrap_or_default(),
             );
             envs.set("TARI_MERGE_MINING_PROXY__MONEROD_USE_AUTH", config.monero_use_auth());
         }
@@ -94,4 +129,17 @@ impl ManagedContainer for MmProxy {
     fn networks(&self, networks: &mut Networks) {
         networks.add("tari_mm_proxy", LocalNet::id());
     }
+
+    fn mounts(&self, mounts: &mut Mounts) {
+        // The process reads the config and writes logs to the data directory
+        if let Some(settings) = self.settings.as_ref().filter(|_| self.runtime == Runtime::Native) {
+            mounts.bind_path(settings.data_directory.display(), VAR_TARI_PATH);
+        }
+    }
+}
+
+impl ManagedProcess for MmProxy {
+    fn program(&self) -> PathBuf {
+        "tari_merge_mining_proxy".into()
+    }
 }
@@ -24,10 +24,13 @@
 use tari_launchpad_protocol::settings::XmRigConfig;
 use tari_sdm::{
     ids::{ManagedTask, TaskId},
-    image::{Args, Envs, ManagedContainer, Networks},
+    image::{checker::ContainerChecker, Args, Envs, ManagedContainer, Networks},
 };
 
-use super::DEFAULT_REGISTRY;
+use super::{
+    mining_stats::{MiningChecker, MiningStatsParser, SharedStats},
+    Origin,
+};
 use crate::resources::{
     config::{ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
     networks::LocalNet,
@@ -37,6 +40,7 @@ use crate::resources::{
 pub struct XMRig {
     settings: Option<ConnectionSettings>,
     xmrig: Option<XmRigConfig>,
+    stats: SharedStats,
 }
 
 impl ManagedTask for XMRig {
@@ -53,13 +57,17 @@ impl ManagedContainer for XMRig {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        DEFAULT_REGISTRY
+        Origin::Tari.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "xmrig"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Tari.tag(&self.settings, self.image_name())
+    }
+
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         self.settings = ConnectionSettings::try_extract(config?);
         let session = &self.settings.as_ref()?.session;
@@ -70,6 +78,10 @@ impl ManagedContainer for XMRig {
         Some(session.all_active || session.merge_layer_active || session.xmrig_active)
     }
 
+    fn checker(&mut self) -> Box<dyn ContainerChecker<LaunchpadProtocol>> {
+        Box::new(MiningChecker::new(MiningStatsParser::xmrig(), &self.stats))
+    }
+
     fn args(&self, args: &mut Args) {
         args.set("--url", "mm_proxy:18081");
         args.set("--user", "${TARI_MONERO_WALLET_ADDRESS}");
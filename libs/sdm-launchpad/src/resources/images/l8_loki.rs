@@ -26,7 +26,7 @@ use tari_sdm::{
     image::{Args, Envs, ManagedContainer, Mounts, Networks, Ports, Volumes},
 };
 
-use super::GRAFANA_REGISTRY;
+use super::Origin;
 use crate::resources::{
     config::{ConnectionSettings, LaunchpadConfig, LaunchpadProtocol},
     images::{Grafana, GENERAL_VOLUME, GRAFANA_VOLUME, LOKI_DEFAULTS_PATH, VAR_TARI_PATH},
@@ -53,13 +53,17 @@ impl ManagedContainer for Loki {
     type Protocol = LaunchpadProtocol;
 
     fn registry(&self) -> &str {
-        GRAFANA_REGISTRY
+        Origin::Grafana.registry(&self.settings, self.image_name())
     }
 
     fn image_name(&self) -> &str {
         "loki"
     }
 
+    fn tag(&self) -> &str {
+        Origin::Grafana.tag(&self.settings, self.image_name())
+    }
+
     fn envs(&self, envs: &mut Envs) {
         let path = concat!(
             "/usr/share/grafana/bin:",
@@ -91,8 +95,16 @@ impl ManagedContainer for Loki {
 
     fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> Option<bool> {
         self.settings = ConnectionSettings::try_extract(config?);
-        let session = &self.settings.as_ref()?.session;
-        Some(session.all_active || session.monitoring_layer_active || session.loki_active)
+        let settings = self.settings.as_ref()?;
+        let session = &settings.session;
+        Some(settings.with_monitoring && (session.all_active || session.monitoring_layer_active || session.loki_active))
+    }
+
+    fn is_available(&self) -> bool {
+        self.settings
+            .as_ref()
+            .map(|settings| settings.with_monitoring)
+            .unwrap_or(true)
     }
 
     fn volumes(&self, volumes: &mut Volumes) {
@@ -0,0 +1,178 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use regex::Regex;
+use tari_launchpad_protocol::mining::{MinerKind, MinerStats, MiningDelta};
+use tari_sdm::image::checker::{CheckerContext, CheckerEvent, ContainerChecker};
+
+use crate::resources::config::{LaunchpadOuterEvent, LaunchpadProtocol};
+
+/// The counters of a miner kept across restarts of its container.
+pub type SharedStats = Arc<Mutex<MinerStats>>;
+
+/// Extracts the stats of a miner from its log records.
+pub struct MiningStatsParser {
+    miner: MinerKind,
+    /// The counters of the previous runs of the miner
+    base: MinerStats,
+    stats: MinerStats,
+    hashrate: Regex,
+    shares: Regex,
+    block: Option<Regex>,
+}
+
+impl MiningStatsParser {
+    /// The SHA3 miner reports the total hashrate over all threads
+    /// and logs every share and found block separately.
+    pub fn sha3() -> Self {
+        Self {
+            miner: MinerKind::Sha3,
+            base: MinerStats::default(),
+            stats: MinerStats::default(),
+            hashrate: Regex::new(r"total\s+(?P<rate>\d+(\.\d+)?)\s*MH/s").unwrap(),
+            shares: Regex::new(r"(?i)\bshare\s+(?P<result>accepted|rejected)\b").unwrap(),
+            block: Some(Regex::new(r"(?i)\bminer found block header\b").unwrap()),
+        }
+    }
+
+    /// XMRig reports the totals of shares on every submission.
+    /// It works in the daemon mode, so every accepted share is a block.
+    pub fn xmrig() -> Self {
+        Self {
+            miner: MinerKind::Xmrig,
+            base: MinerStats::default(),
+            stats: MinerStats::default(),
+            hashrate: Regex::new(r"speed\s+10s/60s/15m\s+(?P<rate>\d+(\.\d+)?)").unwrap(),
+            shares: Regex::new(r"\b(?P<result>accepted|rejected)\s+\((?P<accepted>\d+)/(?P<rejected>\d+)\)").unwrap(),
+            block: None,
+        }
+    }
+
+    /// Continues counting from the stats of the previous run.
+    pub fn resume(mut self, stats: MinerStats) -> Self {
+        self.base = MinerStats { hashrate: 0.0, ..stats };
+        self.stats = self.base.clone();
+        self
+    }
+
+    pub fn miner(&self) -> MinerKind {
+        self.miner
+    }
+
+    pub fn stats(&self) -> &MinerStats {
+        &self.stats
+    }
+
+    /// Returns `true` if the record has changed the stats.
+    pub fn parse(&mut self, record: &str) -> bool {
+        let before = self.stats.clone();
+        if let Some(rate) = self
+            .hashrate
+            .captures(record)
+            .and_then(|caps| caps.name("rate"))
+            .and_then(|rate| rate.as_str().parse::<f64>().ok())
+        {
+            self.stats.hashrate = match self.miner {
+                MinerKind::Sha3 => rate * 1_000_000.0,
+                MinerKind::Xmrig => rate,
+            };
+        }
+        if let Some(caps) = self.shares.captures(record) {
+            let accepted = caps["result"].eq_ignore_ascii_case("accepted");
+            match self.miner {
+                MinerKind::Sha3 if accepted => {
+                    self.stats.accepted_shares += 1;
+                },
+                MinerKind::Sha3 => {
+                    self.stats.rejected_shares += 1;
+                },
+                MinerKind::Xmrig => {
+                    // The totals are counted from the start of the process
+                    if let (Ok(accepted), Ok(rejected)) =
+                        (caps["accepted"].parse::<u64>(), caps["rejected"].parse::<u64>())
+                    {
+                        self.stats.accepted_shares = self.base.accepted_shares + accepted;
+                        self.stats.rejected_shares = self.base.rejected_shares + rejected;
+                        self.stats.blocks_found = self.base.blocks_found + accepted;
+                    }
+                },
+            }
+        }
+        if let Some(block) = self.block.as_ref() {
+            if block.is_match(record) {
+                self.stats.blocks_found += 1;
+            }
+        }
+        self.stats != before
+    }
+}
+
+/// Sends the mining stats to the bus and reports
+/// the miner as ready once it has started.
+pub struct MiningChecker {
+    parser: MiningStatsParser,
+    totals: SharedStats,
+    started: bool,
+}
+
+impl MiningChecker {
+    /// The parser continues from the `totals` and keeps them updated,
+    /// so the counters survive restarts of the container.
+    pub fn new(parser: MiningStatsParser, totals: &SharedStats) -> Self {
+        let stats = totals.lock().map(|stats| stats.clone()).unwrap_or_default();
+        Self {
+            parser: parser.resume(stats),
+            totals: totals.clone(),
+            started: false,
+        }
+    }
+}
+
+#[async_trait]
+impl ContainerChecker<LaunchpadProtocol> for MiningChecker {
+    async fn on_log_event(&mut self, record: &str, ctx: &mut CheckerContext<LaunchpadProtocol>) {
+        if self.parser.parse(record) {
+            if let Ok(mut totals) = self.totals.lock() {
+                *totals = self.parser.stats().clone();
+            }
+            let delta = MiningDelta::UpdateStats {
+                miner: self.parser.miner(),
+                stats: self.parser.stats().clone(),
+            };
+            let event = LaunchpadOuterEvent::MiningDelta(delta);
+            ctx.send_extras(event).ok();
+        }
+    }
+
+    async fn on_interval(&mut self, ctx: &mut CheckerContext<LaunchpadProtocol>) -> Result<(), Error> {
+        if !self.started {
+            ctx.report(CheckerEvent::Ready)?;
+            self.started = true;
+        }
+        Ok(())
+    }
+}
@@ -31,8 +31,11 @@ mod l5_xmrig;
 mod l8_grafana;
 mod l8_loki;
 mod l8_promtail;
+mod mining_stats;
 mod sync_progress;
 
+use std::path::PathBuf;
+
 pub use l1_tor::Tor;
 pub use l2_base_node::TariBaseNode;
 pub use l2_wallet::TariWallet;
@@ -43,9 +46,20 @@ pub use l5_xmrig::XMRig;
 pub use l8_grafana::Grafana;
 pub use l8_loki::Loki;
 pub use l8_promtail::Promtail;
+pub use mining_stats::MiningStatsParser;
+use tari_sdm::{
+    ids::{ManagedTask, TaskId},
+    image::{Args, Envs},
+};
+
+use crate::resources::{
+    config::{host_multiaddr, ConnectionSettings},
+    volumes::SharedVolume,
+};
 
 static DEFAULT_REGISTRY: &str = "quay.io/tarilabs";
 static GRAFANA_REGISTRY: &str = "grafana";
+static DEFAULT_TAG: &str = "latest";
 
 static GENERAL_VOLUME: &str = "/var/tari";
 static BLOCKCHAIN_VOLUME: &str = "/blockchain";
@@ -56,5 +70,94 @@ static BLOCKCHAIN_PATH: &str = "/blockchain";
 // static GRAFANA_PATH: &str = "/grafana";
 static GRAFANA_DEFAULTS_PATH: &str = "/usr/share/grafana/conf/defaults.ini";
 static GRAFANA_PROVISION_PATH: &str = "/etc/grafana/provisioning/datasources/all.yml";
+static LOCALHOST: &str = "127.0.0.1";
+static TOR_SOCKS_PORT: u16 = 9050;
+static TOR_CONTROL_PORT: u16 = 9051;
+
 static LOKI_DEFAULTS_PATH: &str = "/usr/share/grafana/conf/defaults.ini";
 static PROMTAIL_CONFIG_PATH: &str = "/etc/promtail/config.yml";
+
+/// Where an image comes from. Global overrides of the registry
+/// and the tag are applied to images of Tari only.
+#[derive(Debug, Clone, Copy)]
+enum Origin {
+    Tari,
+    Grafana,
+}
+
+impl Origin {
+    fn registry<'a>(self, settings: &'a Option<ConnectionSettings>, image: &str) -> &'a str {
+        let (default, global) = match self {
+            Self::Tari => (DEFAULT_REGISTRY, true),
+            Self::Grafana => (GRAFANA_REGISTRY, false),
+        };
+        settings
+            .as_ref()
+            .and_then(|settings| settings.registry(image, global))
+            .unwrap_or(default)
+    }
+
+    fn tag<'a>(self, settings: &'a Option<ConnectionSettings>, image: &str) -> &'a str {
+        let global = matches!(self, Self::Tari);
+        settings
+            .as_ref()
+            .and_then(|settings| settings.tag(image, global))
+            .unwrap_or(DEFAULT_TAG)
+    }
+}
+
+/// Where the Tari applications are started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runtime {
+    /// In containers of the docker network.
+    Docker,
+    /// As processes of the host. They reach each other and
+    /// the containers by the ports published on the host.
+    Native,
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::Docker
+    }
+}
+
+impl Runtime {
+    /// Returns the host that other applications use to reach the application.
+    fn host(self, hostname: &str) -> &str {
+        match self {
+            Self::Docker => hostname,
+            Self::Native => LOCALHOST,
+        }
+    }
+
+    /// Adds the arguments that the start script of the images adds.
+    fn add_paths(self, args: &mut Args, app: &str) {
+        if self == Self::Native {
+            args.set("--config", format!("{}/config/config.toml", VAR_TARI_PATH));
+            args.set("--base-path", format!("{}/{}", VAR_TARI_PATH, app));
+        }
+    }
+
+    /// Overrides the addresses of Tor from the config file,
+    /// since they are the hosts of the docker network.
+    fn add_tor_addresses(self, envs: &mut Envs, app: &str) {
+        if self == Self::Native {
+            let prefix = format!("TARI_{}__P2P__TRANSPORT__TOR", app);
+            envs.set(
+                &format!("{}__SOCKS_ADDRESS_OVERRIDE", prefix),
+                host_multiaddr(LOCALHOST, TOR_SOCKS_PORT),
+            );
+            envs.set(
+                &format!("{}__CONTROL_ADDRESS", prefix),
+                host_multiaddr(LOCALHOST, TOR_CONTROL_PORT),
+            );
+        }
+    }
+}
+
+/// Returns the directory of the host that replaces the shared volume for processes.
+fn shared_volume_dir(settings: &Option<ConnectionSettings>, volume: &TaskId) -> Option<PathBuf> {
+    let settings = settings.as_ref()?;
+    (*volume == SharedVolume::id()).then(|| settings.data_directory.join("blockchain"))
+}
@@ -50,7 +50,9 @@ impl ManagedVolume for SharedVolume {
 }
 
 #[derive(Debug, Default)]
-pub struct SharedGrafanaVolume {}
+pub struct SharedGrafanaVolume {
+    with_monitoring: Option<bool>,
+}
 
 impl ManagedTask for SharedGrafanaVolume {
     fn id() -> TaskId {
@@ -65,7 +67,14 @@ impl ManagedVolume for SharedGrafanaVolume {
         "shared_grafana_volume"
     }
 
-    fn reconfigure(&mut self, _config: Option<&LaunchpadConfig>) -> bool {
-        true
+    fn reconfigure(&mut self, config: Option<&LaunchpadConfig>) -> bool {
+        self.with_monitoring = config
+            .and_then(|config| config.settings.as_ref())
+            .map(|settings| settings.with_monitoring);
+        self.with_monitoring.unwrap_or_default()
+    }
+
+    fn is_available(&self) -> bool {
+        self.with_monitoring.unwrap_or(true)
     }
 }
@@ -0,0 +1,202 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{anyhow, Error};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{Aead, NewAead},
+    Key,
+    XChaCha20Poly1305,
+    XNonce,
+};
+use rand::{
+    distributions::{Alphanumeric, Distribution},
+    rngs::OsRng,
+    RngCore,
+};
+use serde::{Deserialize, Serialize};
+use tari_utilities::{
+    hex::{from_hex, to_hex},
+    Hidden,
+};
+use tokio::fs;
+
+const STORE_VERSION: u32 = 1;
+const KEY_SIZE: usize = 32;
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 24;
+
+/// The encrypted file of the store.
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    /// Stores saved before the parameters were added used these values.
+    #[serde(default)]
+    kdf: KdfParams,
+    salt: String,
+    nonce: String,
+    data: String,
+}
+
+/// The parameters of argon2 the key was derived with. They are kept
+/// in the file, since the defaults of the crate could change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub algorithm: String,
+    pub version: u32,
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Argon2id.as_str().to_string(),
+            version: Version::V0x13.into(),
+            m_cost: 4_096,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    fn argon2(&self) -> Result<Argon2<'static>, Error> {
+        let algorithm = Algorithm::new(&self.algorithm).map_err(|err| anyhow!("Invalid algorithm: {}", err))?;
+        let version = Version::try_from(self.version).map_err(|err| anyhow!("Invalid version: {}", err))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_SIZE))
+            .map_err(|err| anyhow!("Invalid parameters: {}", err))?;
+        Ok(Argon2::new(algorithm, version, params))
+    }
+}
+
+/// The passwords of the launchpad encrypted with a passphrase of the user.
+///
+/// The key is derived from the passphrase by argon2 and the secrets
+/// are encrypted with XChaCha20-Poly1305 using a new nonce on every save.
+pub struct SecretStore {
+    path: PathBuf,
+    kdf: KdfParams,
+    salt: Vec<u8>,
+    key: Hidden<[u8; KEY_SIZE]>,
+    secrets: BTreeMap<String, Hidden<String>>,
+}
+
+impl SecretStore {
+    /// Decrypts the store with the passphrase or creates
+    /// an empty store if the file doesn't exist yet.
+    pub async fn unlock(path: PathBuf, passphrase: &str) -> Result<Self, Error> {
+        if !path.exists() {
+            log::info!("Creating a new secret store at {}", path.display());
+            let mut salt = vec![0; SALT_SIZE];
+            OsRng.fill_bytes(&mut salt);
+            let kdf = KdfParams::default();
+            let key = derive_key(passphrase, &salt, &kdf)?;
+            return Ok(Self {
+                path,
+                kdf,
+                salt,
+                key,
+                secrets: BTreeMap::new(),
+            });
+        }
+        let data = fs::read_to_string(&path).await?;
+        let file: StoreFile = serde_json::from_str(&data)?;
+        if file.version != STORE_VERSION {
+            return Err(anyhow!("Unsupported version {} of the secret store", file.version));
+        }
+        let salt = from_hex(&file.salt)?;
+        let nonce = from_hex(&file.nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(anyhow!("Invalid nonce of the secret store"));
+        }
+        let key = derive_key(passphrase, &salt, &file.kdf)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(key.reveal()));
+        let encrypted = from_hex(&file.data)?;
+        let decrypted = cipher
+            .decrypt(XNonce::from_slice(&nonce), encrypted.as_ref())
+            .map_err(|_| anyhow!("Wrong passphrase or the secret store is damaged"))?;
+        let decrypted = Hidden::from(decrypted);
+        let secrets = serde_json::from_slice(decrypted.reveal())?;
+        Ok(Self {
+            path,
+            kdf: file.kdf,
+            salt,
+            key,
+            secrets,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Hidden<String>> {
+        self.secrets.get(name)
+    }
+
+    /// Changes the secret in memory. Call `save` to store it.
+    pub fn insert(&mut self, name: &str, value: Hidden<String>) {
+        self.secrets.insert(name.to_string(), value);
+    }
+
+    /// Encrypts the secrets and replaces the file.
+    pub async fn save(&self) -> Result<(), Error> {
+        let mut nonce = [0; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(self.key.reveal()));
+        let decrypted = Hidden::from(serde_json::to_vec(&self.secrets)?);
+        let encrypted = cipher
+            .encrypt(XNonce::from_slice(&nonce), decrypted.reveal().as_ref())
+            .map_err(|_| anyhow!("Can't encrypt the secret store"))?;
+        let file = StoreFile {
+            version: STORE_VERSION,
+            kdf: self.kdf.clone(),
+            salt: to_hex(&self.salt),
+            nonce: to_hex(&nonce),
+            data: to_hex(&encrypted),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let data = serde_json::to_string_pretty(&file)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, data).await?;
+        fs::rename(&tmp_path, &self.path).await?;
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], kdf: &KdfParams) -> Result<Hidden<[u8; KEY_SIZE]>, Error> {
+    let mut key = [0; KEY_SIZE];
+    kdf.argon2()?
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| anyhow!("Can't derive the key of the secret store: {}", err))?;
+    Ok(Hidden::from(key))
+}
+
+/// Creates a cryptographically secure password of the length `len`.
+pub fn create_password(len: usize) -> Hidden<String> {
+    let mut rng = rand::thread_rng();
+    let password = Alphanumeric.sample_iter(&mut rng).take(len).map(char::from).collect();
+    Hidden::from(password)
+}
@@ -21,17 +21,38 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use std::{sync::Mutex, thread::JoinHandle};
+
 use anyhow::Error;
-use tari_launchpad_protocol::{ACTIONS, REACTIONS};
-use tauri::{App, Manager, Wry};
+use tari_launchpad_protocol::{
+    launchpad::{Action, LaunchpadAction},
+    ACTIONS,
+    REACTIONS,
+};
+use tauri::{App, AppHandle, Manager, Wry};
+use tokio::sync::mpsc;
 
 use crate::bus::LaunchpadBus;
+#[cfg(unix)]
+use crate::resources::files::Configurator;
 
 pub fn bus_setup(app: &mut App<Wry>) -> Result<(), Box<dyn std::error::Error>> {
     let handle = app.handle();
+    #[cfg(unix)]
+    {
+        // Two buses would control the same containers
+        let path = Configurator::init()?.socket_path();
+        if std::os::unix::net::UnixStream::connect(path).is_ok() {
+            return Err("The launchpad daemon is running. Stop it to start the app".into());
+        }
+    }
     let bus = LaunchpadBus::start()?;
 
     let in_tx = bus.incoming;
+    app.manage(BusState {
+        incoming: in_tx.clone(),
+        handle: Mutex::new(Some(bus.handle)),
+    });
     let _id = app.listen_global(ACTIONS, move |event| {
         if let Some(payload) = event.payload() {
             let res = serde_json::from_str(payload);
@@ -59,3 +80,24 @@ pub fn bus_setup(app: &mut App<Wry>) -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+struct BusState {
+    incoming: mpsc::UnboundedSender<Action>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Stops all the containers gracefully and blocks until they are down.
+pub fn bus_shutdown(app: &AppHandle<Wry>) {
+    if let Some(state) = app.try_state::<BusState>() {
+        let action = Action::Action(LaunchpadAction::Shutdown);
+        if let Err(err) = state.incoming.send(action) {
+            log::error!("Can't send the shutdown action: {:?}", err);
+        }
+        let handle = state.handle.lock().ok().and_then(|mut handle| handle.take());
+        if let Some(handle) = handle {
+            if handle.join().is_err() {
+                log::error!("Launchpad worker panicked");
+            }
+        }
+    }
+}
@@ -21,14 +21,32 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use std::sync::Arc;
+
 use anyhow::Error;
+use chrono::{TimeZone, Utc};
 use futures::StreamExt;
+use tari_common_types::{emoji::EmojiId, types::PublicKey};
 use tari_launchpad_protocol::{
     launchpad::{LaunchpadDelta, Reaction},
-    wallet::{WalletAction, WalletBalance, WalletDelta, WalletTransaction},
+    wallet::{PaymentResult, TransferFunds, WalletAction, WalletBalance, WalletDelta, WalletTransaction},
 };
+use tari_utilities::hex::Hex;
 use tari_wallet_grpc_client::{
-    grpc::{GetBalanceRequest, GetBalanceResponse, TransactionEventRequest, TransactionEventResponse},
+    grpc::{
+        payment_recipient::PaymentType,
+        GetBalanceRequest,
+        GetBalanceResponse,
+        GetCompletedTransactionsRequest,
+        GetTransactionInfoRequest,
+        PaymentRecipient,
+        TransactionDirection,
+        TransactionEventRequest,
+        TransactionEventResponse,
+        TransactionInfo,
+        TransactionStatus,
+        TransferRequest,
+    },
     WalletGrpcClient,
 };
 use tokio::{
@@ -37,22 +55,31 @@ use tokio::{
     time::{interval, sleep, Duration},
 };
 
+use crate::history::TransactionStore;
+
 pub struct WalletGrpc {
-    _tx: mpsc::UnboundedSender<WalletAction>,
+    tx: mpsc::UnboundedSender<WalletAction>,
 }
 
 impl WalletGrpc {
-    pub fn new(out_tx: mpsc::UnboundedSender<Reaction>) -> Self {
+    pub fn new(out_tx: mpsc::UnboundedSender<Reaction>, history: Arc<TransactionStore>) -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
-        let worker = WalletGrpcWorker { rx, out_tx };
+        let worker = WalletGrpcWorker { rx, out_tx, history };
         tokio::spawn(worker.entrypoint());
-        Self { _tx: tx }
+        Self { tx }
+    }
+
+    pub fn send(&self, action: WalletAction) -> Result<(), Error> {
+        self.tx
+            .send(action)
+            .map_err(|_| Error::msg("The wallet grpc worker has stopped"))
     }
 }
 
 struct WalletGrpcWorker {
     rx: mpsc::UnboundedReceiver<WalletAction>,
     out_tx: mpsc::UnboundedSender<Reaction>,
+    history: Arc<TransactionStore>,
 }
 
 impl WalletGrpcWorker {
@@ -67,7 +94,9 @@ impl WalletGrpcWorker {
                 loop {
                     let action = self.rx.try_recv();
                     match action {
-                        Ok(_) => {},
+                        Ok(action) => {
+                            self.reject_action(action).ok();
+                        },
                         Err(TryRecvError::Disconnected) => {
                             return;
                         },
@@ -92,20 +121,22 @@ impl WalletGrpcWorker {
         let delta = WalletDelta::SetActive(true);
         self.send_update(delta)?;
 
+        self.backfill_history(&mut client).await?;
+
         let mut update = interval(Duration::from_millis(5_000));
 
         loop {
             select! {
                 action = self.rx.recv() => {
-                    if let Some(_action) = action {
-                        // TODO: Process action
+                    if let Some(action) = action {
+                        self.process_action(&mut client, action).await?;
                     } else {
                         break;
                     }
                 }
                 event = transaction_events.next() => {
                     if let Some(response) = event.transpose()? {
-                        self.process_transaction_response(response)?;
+                        self.process_transaction_response(&mut client, response).await?;
                     }
                 }
                 _ = update.tick() => {
@@ -118,6 +149,99 @@ impl WalletGrpcWorker {
         Ok(())
     }
 
+    async fn process_action(&mut self, client: &mut WalletGrpcClient, action: WalletAction) -> Result<(), Error> {
+        match action {
+            WalletAction::TransferFunds(funds) => self.transfer_funds(client, funds).await,
+        }
+    }
+
+    /// Reports the failure of an action received while the wallet is not connected.
+    fn reject_action(&mut self, action: WalletAction) -> Result<(), Error> {
+        match action {
+            WalletAction::TransferFunds(funds) => {
+                for payment in funds.payments {
+                    let result = PaymentResult {
+                        address: payment.address,
+                        tx_id: 0,
+                        is_success: false,
+                        failure_message: "The wallet is not connected".into(),
+                    };
+                    self.send_update(WalletDelta::LogPayment(result))?;
+                }
+                Ok(())
+            },
+        }
+    }
+
+    async fn transfer_funds(&mut self, client: &mut WalletGrpcClient, funds: TransferFunds) -> Result<(), Error> {
+        let mut recipients = Vec::new();
+        for payment in funds.payments {
+            match parse_address(&payment.address) {
+                Some(public_key) => {
+                    let payment_type = if payment.one_sided {
+                        PaymentType::OneSided
+                    } else {
+                        PaymentType::StandardMimblewimble
+                    };
+                    let recipient = PaymentRecipient {
+                        address: public_key.to_hex(),
+                        amount: payment.amount,
+                        fee_per_gram: payment.fee_per_gram,
+                        message: payment.message,
+                        payment_type: payment_type as i32,
+                    };
+                    recipients.push(recipient);
+                },
+                None => {
+                    let result = PaymentResult {
+                        address: payment.address,
+                        tx_id: 0,
+                        is_success: false,
+                        failure_message: "Invalid address or emoji id".into(),
+                    };
+                    self.send_update(WalletDelta::LogPayment(result))?;
+                },
+            }
+        }
+        if recipients.is_empty() {
+            return Ok(());
+        }
+        let addresses: Vec<_> = recipients.iter().map(|recipient| recipient.address.clone()).collect();
+        let request = TransferRequest { recipients };
+        let results: Vec<PaymentResult> = match client.transfer(request).await {
+            Ok(response) => response
+                .into_inner()
+                .results
+                .into_iter()
+                .map(|value| PaymentResult {
+                    address: value.address,
+                    tx_id: value.transaction_id,
+                    is_success: value.is_success,
+                    failure_message: value.failure_message,
+                })
+                .collect(),
+            Err(status) => {
+                // The wallet has rejected the whole request
+                addresses
+                    .into_iter()
+                    .map(|address| PaymentResult {
+                        address,
+                        tx_id: 0,
+                        is_success: false,
+                        failure_message: status.message().to_string(),
+                    })
+                    .collect()
+            },
+        };
+        for result in results {
+            if !result.is_success {
+                log::warn!("Transfer to {} failed: {}", result.address, result.failure_message);
+            }
+            self.send_update(WalletDelta::LogPayment(result))?;
+        }
+        Ok(())
+    }
+
     fn process_balance(&mut self, response: GetBalanceResponse) -> Result<(), Error> {
         let balance = WalletBalance {
             available: response.available_balance,
@@ -128,9 +252,27 @@ impl WalletGrpcWorker {
         self.send_update(delta)
     }
 
-    fn process_transaction_response(&mut self, response: TransactionEventResponse) -> Result<(), Error> {
+    /// Stores the transactions completed while the launchpad was not running.
+    async fn backfill_history(&mut self, client: &mut WalletGrpcClient) -> Result<(), Error> {
+        let request = GetCompletedTransactionsRequest {};
+        let mut stream = client.get_completed_transactions(request).await?.into_inner();
+        let mut transactions = Vec::new();
+        while let Some(response) = stream.next().await.transpose()? {
+            if let Some(info) = response.transaction {
+                transactions.push(from_info(info));
+            }
+        }
+        log::debug!("Backfilling {} transactions of the wallet", transactions.len());
+        self.history.upsert(transactions).await
+    }
+
+    async fn process_transaction_response(
+        &mut self,
+        client: &mut WalletGrpcClient,
+        response: TransactionEventResponse,
+    ) -> Result<(), Error> {
         if let Some(value) = response.transaction {
-            let wt = WalletTransaction {
+            let mut wt = WalletTransaction {
                 event: value.event,
                 tx_id: value.tx_id,
                 source_pk: value.source_pk,
@@ -138,9 +280,30 @@ impl WalletGrpcWorker {
                 status: value.status,
                 direction: value.direction,
                 amount: value.amount,
+                fee: 0,
                 message: value.message,
                 is_coinbase: value.is_coinbase,
+                timestamp: Utc::now().naive_utc(),
             };
+            // Events have no fees and timestamps
+            if let Ok(tx_id) = wt.tx_id.parse() {
+                let request = GetTransactionInfoRequest {
+                    transaction_ids: vec![tx_id],
+                };
+                match client.get_transaction_info(request).await {
+                    Ok(response) => {
+                        if let Some(info) = response.into_inner().transactions.into_iter().next() {
+                            let info = from_info(info);
+                            wt.fee = info.fee;
+                            wt.timestamp = info.timestamp;
+                        }
+                    },
+                    Err(err) => {
+                        log::warn!("Can't get the info of the transaction {}: {}", tx_id, err);
+                    },
+                }
+            }
+            self.history.upsert(vec![wt.clone()]).await?;
             let delta = WalletDelta::LogTransaction(wt);
             self.send_update(delta)?;
         }
@@ -154,3 +317,104 @@ impl WalletGrpcWorker {
             .map_err(|_| Error::msg("Can't send update for the wallet"))
     }
 }
+
+/// Accepts a public key in hex or an emoji id.
+fn parse_address(address: &str) -> Option<PublicKey> {
+    let address = address.trim();
+    EmojiId::str_to_pubkey(&address.replace('|', ""))
+        .ok()
+        .or_else(|| PublicKey::from_hex(address).ok())
+}
+
+fn from_info(info: TransactionInfo) -> WalletTransaction {
+    let status = TransactionStatus::from_i32(info.status);
+    let status_name = if info.is_cancelled {
+        "Cancelled".to_string()
+    } else {
+        status.map(|status| format!("{:?}", status)).unwrap_or_default()
+    };
+    let direction = TransactionDirection::from_i32(info.direction);
+    // The grpc has no flag, but the wallet pays a coinbase to itself and keeps
+    // the status of it until it's confirmed. Messages are set by senders,
+    // so they can't mark a coinbase.
+    let is_coinbase = status == Some(TransactionStatus::Coinbase) ||
+        (direction == Some(TransactionDirection::Inbound) &&
+            !info.source_pk.is_empty() &&
+            info.source_pk == info.dest_pk);
+    let direction = direction
+        .map(|direction| format!("{:?}", direction))
+        .unwrap_or_default();
+    let timestamp = Utc
+        .timestamp_opt(info.timestamp as i64, 0)
+        .single()
+        .map(|timestamp| timestamp.naive_utc())
+        .unwrap_or_else(|| Utc::now().naive_utc());
+    WalletTransaction {
+        event: String::new(),
+        tx_id: info.tx_id.to_string(),
+        source_pk: info.source_pk,
+        dest_pk: info.dest_pk,
+        status: status_name,
+        direction,
+        amount: info.amount,
+        fee: info.fee,
+        is_coinbase,
+        message: info.message,
+        timestamp,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tari_utilities::hex::from_hex;
+
+    use super::*;
+
+    /// The compressed Ristretto base point
+    const PUBLIC_KEY: &str = "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76";
+
+    #[test]
+    fn test_parse_address() {
+        let public_key = PublicKey::from_hex(PUBLIC_KEY).unwrap();
+        let emoji_id = EmojiId::from_public_key(&public_key).to_string();
+        assert_eq!(parse_address(&emoji_id), Some(public_key.clone()));
+
+        let separated = emoji_id.chars().map(String::from).collect::<Vec<_>>().join("|");
+        assert_eq!(parse_address(&separated), Some(public_key.clone()));
+
+        assert_eq!(parse_address(PUBLIC_KEY), Some(public_key.clone()));
+        assert_eq!(parse_address(&format!(" {} ", PUBLIC_KEY)), Some(public_key));
+
+        assert_eq!(parse_address(""), None);
+        assert_eq!(parse_address("not an address"), None);
+        assert_eq!(parse_address(&PUBLIC_KEY[1..]), None);
+    }
+
+    #[test]
+    fn test_coinbase_detected() {
+        let own_pk = from_hex(PUBLIC_KEY).unwrap();
+        let coinbase = |status: TransactionStatus, source_pk: Vec<u8>| TransactionInfo {
+            status: status as i32,
+            direction: TransactionDirection::Inbound as i32,
+            source_pk,
+            dest_pk: own_pk.clone(),
+            ..Default::default()
+        };
+        // Unconfirmed and confirmed while the launchpad was stopped
+        assert!(from_info(coinbase(TransactionStatus::Coinbase, own_pk.clone())).is_coinbase);
+        assert!(from_info(coinbase(TransactionStatus::MinedConfirmed, own_pk.clone())).is_coinbase);
+        // A payment from another wallet
+        assert!(!from_info(coinbase(TransactionStatus::MinedConfirmed, vec![1; 32])).is_coinbase);
+
+        let outbound = TransactionInfo {
+            direction: TransactionDirection::Outbound as i32,
+            ..coinbase(TransactionStatus::MinedConfirmed, own_pk.clone())
+        };
+        assert!(!from_info(outbound).is_coinbase);
+        let unknown = TransactionInfo {
+            dest_pk: Vec::new(),
+            ..coinbase(TransactionStatus::MinedConfirmed, Vec::new())
+        };
+        assert!(!from_info(unknown).is_coinbase);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{convert::identity, pin::Pin};
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use tari_launchpad_protocol::{
     container::{TaskId, TaskStatus},
     launchpad::{Action, LaunchpadAction, LaunchpadState, Reaction},
@@ -97,6 +97,9 @@ impl TestStateInner {
                     state.apply(delta);
                 }
             },
+            Reaction::ActionFailed { action, reason } => {
+                return Err(anyhow!("The action {} failed: {}", action, reason));
+            },
         }
         Ok(())
     }
@@ -0,0 +1,135 @@
+#![cfg(unix)]
+
+use std::{env, os::unix::fs::PermissionsExt, path::PathBuf, process};
+
+use anyhow::Error;
+use tari_launchpad_protocol::{
+    launchpad::{Action, LaunchpadAction, LaunchpadDelta, Reaction},
+    wallet::{SeedWordsBackup, WalletDelta, SEED_WORDS_COUNT},
+};
+use tari_sdm_launchpad::daemon::{self, DaemonClient};
+use tokio::sync::mpsc;
+
+fn socket_path(name: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("tari-launchpad-{}-{}", name, process::id()));
+    path.push("launchpad.sock");
+    path
+}
+
+/// Skips the reactions until the order of the tasks is set.
+async fn wait_tasks_order(client: &mut DaemonClient) -> Vec<Reaction> {
+    let mut reactions = Vec::new();
+    while let Some(reaction) = client.outgoing.recv().await {
+        let done = matches!(&reaction, Reaction::Delta(LaunchpadDelta::TasksOrder(_)));
+        reactions.push(reaction);
+        if done {
+            break;
+        }
+    }
+    reactions
+}
+
+#[tokio::test]
+async fn test_daemon_attach_and_detach() -> Result<(), Error> {
+    let path = socket_path("daemon");
+    let (in_tx, mut in_rx) = mpsc::unbounded_channel();
+    let (out_tx, out_rx) = mpsc::unbounded_channel();
+    let server = tokio::spawn(daemon::serve(path.clone(), in_tx, out_rx));
+    out_tx.send(Reaction::Delta(LaunchpadDelta::TasksOrder(vec!["Tor".into()])))?;
+    while !daemon::is_running(&path).await {
+        tokio::task::yield_now().await;
+    }
+
+    // The state is sent first and the deltas follow it
+    let mut client = DaemonClient::attach(&path).await?;
+    let mut state = match client.outgoing.recv().await {
+        Some(Reaction::State(state)) => state,
+        other => panic!("Unexpected reaction: {:?}", other),
+    };
+    while state.tasks_order.is_empty() {
+        match client.outgoing.recv().await {
+            Some(Reaction::Delta(delta)) => state.apply(delta),
+            other => panic!("Unexpected reaction: {:?}", other),
+        }
+    }
+    assert_eq!(state.tasks_order, vec!["Tor".into()]);
+    let mode = std::fs::metadata(path.parent().unwrap())?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+    client.incoming.send(Action::Action(LaunchpadAction::Connect))?;
+    assert!(matches!(
+        in_rx.recv().await,
+        Some(Action::Action(LaunchpadAction::Connect))
+    ));
+    drop(client);
+
+    // The daemon stops with the bus
+    drop(out_tx);
+    server.await??;
+    assert!(!path.exists());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_seed_words_sent_to_requester() -> Result<(), Error> {
+    let path = socket_path("reveal");
+    let (in_tx, mut in_rx) = mpsc::unbounded_channel();
+    let (out_tx, out_rx) = mpsc::unbounded_channel();
+    let server = tokio::spawn(daemon::serve(path.clone(), in_tx, out_rx));
+    let backup = WalletDelta::UpdateBackup(SeedWordsBackup::Pending);
+    out_tx.send(Reaction::Delta(LaunchpadDelta::WalletDelta(backup)))?;
+    while !daemon::is_running(&path).await {
+        tokio::task::yield_now().await;
+    }
+    let mut requester = DaemonClient::attach(&path).await?;
+    let mut other = DaemonClient::attach(&path).await?;
+    assert!(matches!(requester.outgoing.recv().await, Some(Reaction::State(_))));
+    assert!(matches!(other.outgoing.recv().await, Some(Reaction::State(_))));
+
+    requester
+        .incoming
+        .send(Action::Action(LaunchpadAction::RevealSeedWords))?;
+    assert!(matches!(
+        in_rx.recv().await,
+        Some(Action::Action(LaunchpadAction::RevealSeedWords))
+    ));
+    let words = vec!["abandon".to_string(); SEED_WORDS_COUNT];
+    out_tx.send(Reaction::Delta(LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(
+        words.clone().into(),
+    ))))?;
+    out_tx.send(Reaction::Delta(LaunchpadDelta::TasksOrder(vec!["Tor".into()])))?;
+
+    // The order of the reply and the broadcast deltas is not defined
+    let reactions = wait_tasks_order(&mut requester).await;
+    let revealed = reactions.iter().any(|reaction| {
+        matches!(
+            reaction,
+            Reaction::Delta(LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(hidden)))
+                if hidden.reveal() == &words
+        )
+    });
+    if !revealed {
+        assert!(matches!(
+            requester.outgoing.recv().await,
+            Some(Reaction::Delta(LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(_))))
+        ));
+    }
+    let reactions = wait_tasks_order(&mut other).await;
+    assert!(reactions.iter().all(|reaction| !matches!(
+        reaction,
+        Reaction::Delta(LaunchpadDelta::WalletDelta(WalletDelta::SeedWords(_)))
+    )));
+
+    // A client attached later doesn't get the words with the state
+    let mut late = DaemonClient::attach(&path).await?;
+    match late.outgoing.recv().await {
+        Some(Reaction::State(state)) => assert!(state.wallet.seed_words.is_none()),
+        other => panic!("Unexpected reaction: {:?}", other),
+    }
+    drop((requester, other, late));
+    drop(out_tx);
+    server.await??;
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use tari_launchpad_protocol::wallet::WalletTransaction;
+use tari_sdm_launchpad::export::{export_path, Ledger};
+
+fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2022, 10, day)
+        .and_then(|date| date.and_hms_opt(hour, 0, 0))
+        .unwrap()
+}
+
+fn transaction(
+    tx_id: u64,
+    direction: &str,
+    status: &str,
+    is_coinbase: bool,
+    timestamp: NaiveDateTime,
+) -> WalletTransaction {
+    WalletTransaction {
+        event: String::new(),
+        tx_id: tx_id.to_string(),
+        source_pk: vec![0xab],
+        dest_pk: vec![0xcd],
+        status: status.into(),
+        direction: direction.into(),
+        amount: 1_000,
+        fee: 10,
+        message: String::new(),
+        is_coinbase,
+        timestamp,
+    }
+}
+
+#[test]
+fn test_daily_totals() {
+    let ledger = Ledger::new(
+        vec![
+            transaction(1, "Inbound", "MinedConfirmed", true, at(2, 10)),
+            transaction(2, "Outbound", "Broadcast", false, at(1, 18)),
+            transaction(3, "Inbound", "Mined Confirmed", false, at(1, 9)),
+            transaction(4, "Outbound", "Cancelled", false, at(1, 12)),
+        ],
+        &Utc,
+    );
+    let ids: Vec<_> = ledger.transactions.iter().map(|tx| tx.tx_id.as_str()).collect();
+    assert_eq!(ids, ["3", "4", "2", "1"]);
+    assert_eq!(ledger.daily_totals.len(), 2);
+    let first = &ledger.daily_totals[0];
+    assert_eq!(first.date, at(1, 0).date());
+    assert_eq!(first.transactions, 2);
+    assert_eq!(
+        (first.received, first.sent, first.mined, first.fees),
+        (1_000, 1_000, 0, 10)
+    );
+    let second = &ledger.daily_totals[1];
+    assert_eq!(
+        (second.received, second.sent, second.mined, second.fees),
+        (0, 0, 1_000, 0)
+    );
+}
+
+#[test]
+fn test_csv_escaping() {
+    let mut tx = transaction(1, "Outbound", "Completed", false, at(1, 0));
+    tx.message = "Rent, \"October\"".into();
+    let ledger = Ledger::new(vec![tx], &Utc);
+    let csv = ledger.transactions_csv();
+    let row = csv.lines().nth(1).unwrap();
+    assert_eq!(
+        row,
+        "2022-10-01T00:00:00+00:00,1,Outbound,Completed,1000,10,false,ab,cd,\"Rent, \"\"October\"\"\""
+    );
+    assert_eq!(
+        ledger.daily_totals_csv(),
+        "date,transactions,received,sent,mined,fees\n2022-10-01,1,0,1000,0,10\n"
+    );
+}
+
+#[test]
+fn test_csv_formulas_neutralized() {
+    let messages = [
+        (
+            "=HYPERLINK(\"http://example.com\")",
+            "\"'=HYPERLINK(\"\"http://example.com\"\")\"",
+        ),
+        ("+1", "'+1"),
+        ("-1", "'-1"),
+        ("@SUM(A1)", "'@SUM(A1)"),
+        ("\tcmd", "'\tcmd"),
+        ("\rcmd", "\"'\rcmd\""),
+        ("Rent 1+1", "Rent 1+1"),
+    ];
+    for (message, field) in messages {
+        let mut tx = transaction(1, "Outbound", "Completed", false, at(1, 0));
+        tx.message = message.into();
+        let csv = Ledger::new(vec![tx], &Utc).transactions_csv();
+        assert!(csv.ends_with(&format!(",{}\n", field)), "{:?}", csv);
+    }
+}
+
+#[test]
+fn test_daily_totals_in_time_zone() {
+    let transactions = vec![
+        transaction(1, "Inbound", "MinedConfirmed", false, at(1, 20)),
+        transaction(2, "Inbound", "MinedConfirmed", false, at(2, 1)),
+    ];
+    let ledger = Ledger::new(transactions.clone(), &Utc);
+    assert_eq!(ledger.daily_totals.len(), 2);
+
+    // 20:00 UTC is the next day in UTC+5
+    let tz = FixedOffset::east_opt(5 * 3600).unwrap();
+    let ledger = Ledger::new(transactions, &tz);
+    assert_eq!(ledger.daily_totals.len(), 1);
+    assert_eq!(ledger.daily_totals[0].date, at(2, 0).date());
+    assert_eq!(ledger.daily_totals[0].transactions, 2);
+}
+
+#[test]
+fn test_export_path() {
+    let dir = Path::new("/data/exports");
+    assert_eq!(export_path(dir, "ledger.csv").unwrap(), dir.join("ledger.csv"));
+    assert!(export_path(dir, "").is_err());
+    assert!(export_path(dir, "..").is_err());
+    assert!(export_path(dir, "../ledger.csv").is_err());
+    assert!(export_path(dir, "nested/ledger.csv").is_err());
+    assert!(export_path(dir, "/tmp/ledger.csv").is_err());
+}
@@ -0,0 +1,102 @@
+use std::{env, path::PathBuf, process};
+
+use anyhow::Error;
+use chrono::{NaiveDate, NaiveDateTime};
+use tari_launchpad_protocol::wallet::{TransactionQuery, WalletTransaction};
+use tari_sdm_launchpad::history::TransactionStore;
+
+fn store_path(name: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("tari-launchpad-history-{}-{}", name, process::id()));
+    path.push("transactions.jsonl");
+    path
+}
+
+fn day(day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2022, 10, day)
+        .and_then(|date| date.and_hms_opt(12, 0, 0))
+        .unwrap()
+}
+
+fn transaction(tx_id: u64, direction: &str, is_coinbase: bool, timestamp: NaiveDateTime) -> WalletTransaction {
+    WalletTransaction {
+        event: String::new(),
+        tx_id: tx_id.to_string(),
+        source_pk: Vec::new(),
+        dest_pk: Vec::new(),
+        status: "MinedConfirmed".into(),
+        direction: direction.into(),
+        amount: tx_id * 1_000,
+        fee: 0,
+        message: String::new(),
+        is_coinbase,
+        timestamp,
+    }
+}
+
+#[tokio::test]
+async fn test_history_persisted() -> Result<(), Error> {
+    let path = store_path("persisted");
+    let store = TransactionStore::open(path.clone()).await?;
+    let transactions = (1..=5)
+        .map(|id| transaction(id, "Inbound", true, day(id as u32)))
+        .collect();
+    store.upsert(transactions).await?;
+    let mut update = transaction(3, "Inbound", false, day(20));
+    update.fee = 25;
+    store.upsert(vec![update]).await?;
+    drop(store);
+
+    let store = TransactionStore::open(path.clone()).await?;
+    let page = store.query(TransactionQuery::default()).await;
+    assert_eq!(page.total, 5);
+    let merged = page.transactions.iter().find(|tx| tx.tx_id == "3").unwrap();
+    assert_eq!(merged.fee, 25);
+    assert!(merged.is_coinbase);
+    assert_eq!(merged.timestamp, day(3));
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_history_query() -> Result<(), Error> {
+    let path = store_path("query");
+    let store = TransactionStore::open(path.clone()).await?;
+    let transactions = (1..=10)
+        .map(|id| {
+            let direction = if id % 2 == 0 { "Inbound" } else { "Outbound" };
+            transaction(id, direction, id % 2 == 0, day(id as u32))
+        })
+        .collect();
+    store.upsert(transactions).await?;
+
+    let query = TransactionQuery {
+        limit: 3,
+        ..Default::default()
+    };
+    let page = store.query(query).await;
+    assert_eq!(page.total, 10);
+    let ids: Vec<_> = page.transactions.iter().map(|tx| tx.tx_id.as_str()).collect();
+    assert_eq!(ids, ["10", "9", "8"]);
+
+    let query = TransactionQuery {
+        direction: Some("inbound".into()),
+        is_coinbase: Some(true),
+        from: Some(day(3)),
+        to: Some(day(9)),
+        offset: 1,
+        ..Default::default()
+    };
+    let page = store.query(query).await;
+    assert_eq!(page.total, 3);
+    let ids: Vec<_> = page.transactions.iter().map(|tx| tx.tx_id.as_str()).collect();
+    assert_eq!(ids, ["6", "4"]);
+
+    let query = TransactionQuery {
+        status: Some("Mined Confirmed".into()),
+        ..Default::default()
+    };
+    assert_eq!(store.query(query).await.total, 10);
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use tari_launchpad_protocol::mining::MinerStats;
+use tari_sdm_launchpad::resources::images::MiningStatsParser;
+
+#[test]
+fn test_sha3_stats() {
+    let mut parser = MiningStatsParser::sha3();
+    assert!(!parser.parse("Starting miner with 7 threads"));
+    assert!(parser.parse("Miner 0 reported 1.50MH/s with total 10.50MH/s over 7 threads. Height: 1234. Target: 5000"));
+    assert_eq!(parser.stats().hashrate, 10_500_000.0);
+    assert!(parser.parse("Miner found block header BlockHeader { height: 1234 } with difficulty 5000"));
+    assert!(parser.parse("Share accepted"));
+    assert!(parser.parse("Share rejected: stale"));
+    let stats = parser.stats();
+    assert_eq!(stats.blocks_found, 1);
+    assert_eq!(stats.accepted_shares, 1);
+    assert_eq!(stats.rejected_shares, 1);
+}
+
+#[test]
+fn test_xmrig_stats() {
+    let mut parser = MiningStatsParser::xmrig();
+    assert!(!parser.parse("[2022-10-10 12:00:00.000]  miner    speed 10s/60s/15m n/a n/a n/a H/s max n/a H/s"));
+    assert!(parser.parse("[2022-10-10 12:01:00.000]  miner    speed 10s/60s/15m 1234.5 1200.0 n/a H/s max 1300.0 H/s"));
+    assert_eq!(parser.stats().hashrate, 1234.5);
+    assert!(parser.parse("[2022-10-10 12:02:00.000]  cpu      accepted (3/0) diff 120001 (43 ms)"));
+    assert!(
+        parser.parse("[2022-10-10 12:03:00.000]  cpu      rejected (3/1) diff 120001 \"Low difficulty share\" (40 ms)")
+    );
+    let stats = parser.stats();
+    assert_eq!(stats.accepted_shares, 3);
+    assert_eq!(stats.rejected_shares, 1);
+    assert_eq!(stats.blocks_found, 3);
+}
+
+#[test]
+fn test_xmrig_stats_resumed() {
+    let previous = MinerStats {
+        hashrate: 1234.5,
+        accepted_shares: 3,
+        rejected_shares: 1,
+        blocks_found: 3,
+    };
+    let mut parser = MiningStatsParser::xmrig().resume(previous);
+    assert_eq!(parser.stats().hashrate, 0.0);
+    assert!(parser.parse("[2022-10-10 12:10:00.000]  cpu      accepted (1/0) diff 120001 (43 ms)"));
+    let stats = parser.stats();
+    assert_eq!(stats.accepted_shares, 4);
+    assert_eq!(stats.rejected_shares, 1);
+    assert_eq!(stats.blocks_found, 4);
+}
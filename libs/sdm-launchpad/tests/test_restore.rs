@@ -0,0 +1,111 @@
+use std::{
+    env,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use anyhow::Error;
+use tari_launchpad_protocol::{
+    config::LaunchpadConfig,
+    session::LaunchpadSession,
+    settings::{LaunchpadSettings, WalletConfig},
+    wallet::{WalletRestore, SEED_WORDS_COUNT},
+};
+use tari_sdm::image::{Args, Envs, ManagedContainer};
+use tari_sdm_launchpad::resources::{
+    files::{Configurator, RESTORE_WORDS_PATH},
+    images::TariWallet,
+};
+
+fn base_dir(name: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("tari-launchpad-restore-{}-{}", name, process::id()));
+    path
+}
+
+fn seed_words() -> Vec<String> {
+    vec!["abandon".to_string(); SEED_WORDS_COUNT]
+}
+
+fn config(base_dir: &Path, restore: Option<WalletRestore>) -> LaunchpadConfig {
+    let mut wallet = WalletConfig::default();
+    wallet.password.value = Some("123".to_string().into());
+    let settings = LaunchpadSettings {
+        wallet: Some(wallet),
+        data_directory: base_dir.to_path_buf(),
+        ..Default::default()
+    };
+    LaunchpadConfig {
+        session: LaunchpadSession {
+            wallet_active: true,
+            ..Default::default()
+        },
+        settings: Some(settings),
+        restore,
+    }
+}
+
+#[tokio::test]
+async fn test_wallet_archived() -> Result<(), Error> {
+    let base_dir = base_dir("archive");
+    fs::create_dir_all(base_dir.join("wallet"))?;
+    fs::create_dir_all(base_dir.join("config"))?;
+    fs::write(base_dir.join("wallet").join("console_wallet.db"), "wallet")?;
+    fs::write(base_dir.join("config").join("seed_words.txt"), "old words")?;
+    let configurator = Configurator::with_base_dir(base_dir.clone());
+    assert!(configurator.has_seed_words());
+    assert!(configurator.has_wallet());
+
+    let backup_dir = configurator.archive_wallet().await?;
+    assert!(backup_dir.join("console_wallet.db").exists());
+    assert!(backup_dir.join("seed_words.txt").exists());
+    assert!(!configurator.has_seed_words());
+    assert!(!configurator.has_wallet());
+    assert_eq!(fs::read_dir(base_dir.join("wallet"))?.count(), 0);
+    fs::remove_dir_all(base_dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pending_restore() -> Result<(), Error> {
+    let base_dir = base_dir("pending");
+    fs::create_dir_all(base_dir.join("config"))?;
+    let configurator = Configurator::with_base_dir(base_dir.clone());
+    let words = seed_words();
+    configurator.write_restore_words(&words).await?;
+    assert!(configurator.has_pending_restore());
+    assert_eq!(configurator.read_restore_words().await?, words);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(base_dir.join(RESTORE_WORDS_PATH))?.permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // A new wallet is not created while the restore is pending
+    let mut wallet = TariWallet::default();
+    assert_eq!(wallet.reconfigure(Some(&config(&base_dir, None))), Some(false));
+
+    let restore = WalletRestore {
+        seed_words: words.into(),
+        birthday: Some(19_000),
+    };
+    assert_eq!(wallet.reconfigure(Some(&config(&base_dir, Some(restore)))), Some(true));
+    let mut args = Args::default();
+    wallet.args(&mut args);
+    let args = args.build();
+    assert!(args.contains(&"--recovery".to_string()));
+    assert!(args.iter().all(|arg| !arg.contains("abandon")));
+    let mut envs = Envs::default();
+    wallet.envs(&mut envs);
+    let envs = envs.build();
+    assert!(envs.contains(&"RECOVERY_SEED_WORDS_FILE=/var/tari/config/restore_words.txt".to_string()));
+    assert!(envs.contains(&"TARI_WALLET__RECOVERY_BIRTHDAY=19000".to_string()));
+
+    configurator.delete_restore_words().await?;
+    assert!(!configurator.has_pending_restore());
+    assert_eq!(wallet.reconfigure(Some(&config(&base_dir, None))), Some(true));
+    fs::remove_dir_all(base_dir)?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::{env, path::PathBuf, process};
+
+use anyhow::Error;
+use tari_sdm_launchpad::secrets::{KdfParams, SecretStore};
+
+fn store_path(name: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    path.push(format!("tari-launchpad-secrets-{}-{}", name, process::id()));
+    path.push("secrets.json");
+    path
+}
+
+#[tokio::test]
+async fn test_secrets_persisted() -> Result<(), Error> {
+    let path = store_path("persisted");
+    let mut store = SecretStore::unlock(path.clone(), "passphrase").await?;
+    assert!(store.get("wallet_password").is_none());
+    store.insert("wallet_password", "secret".to_string().into());
+    store.save().await?;
+
+    let data = std::fs::read_to_string(&path)?;
+    assert!(!data.contains("secret"));
+
+    let store = SecretStore::unlock(path.clone(), "passphrase").await?;
+    let password = store.get("wallet_password").map(|value| value.reveal().clone());
+    assert_eq!(password.as_deref(), Some("secret"));
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_secrets_wrong_passphrase() -> Result<(), Error> {
+    let path = store_path("wrong");
+    let mut store = SecretStore::unlock(path.clone(), "passphrase").await?;
+    store.insert("tor_control_password", "tari".to_string().into());
+    store.save().await?;
+
+    assert!(SecretStore::unlock(path.clone(), "other").await.is_err());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_secrets_kdf_params_persisted() -> Result<(), Error> {
+    let path = store_path("kdf");
+    let mut store = SecretStore::unlock(path.clone(), "passphrase").await?;
+    store.insert("wallet_password", "secret".to_string().into());
+    store.save().await?;
+
+    let data = std::fs::read_to_string(&path)?;
+    let mut file: serde_json::Value = serde_json::from_str(&data)?;
+    let kdf: KdfParams = serde_json::from_value(file["kdf"].clone())?;
+    assert_eq!(kdf, KdfParams::default());
+
+    // The key is derived with the parameters of the file
+    file["kdf"]["m_cost"] = (kdf.m_cost * 2).into();
+    std::fs::write(&path, serde_json::to_string(&file)?)?;
+    assert!(SecretStore::unlock(path.clone(), "passphrase").await.is_err());
+    std::fs::remove_dir_all(path.parent().unwrap())?;
+    Ok(())
+}
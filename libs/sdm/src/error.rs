@@ -21,8 +21,23 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
+use tari_launchpad_protocol::container::TaskId;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 #[error("Can't parse value: {0}")]
 pub struct ParseError(pub String);
+
+#[derive(Debug, Error)]
+pub enum GraphError {
+    #[error("Task {0} is registered twice")]
+    Duplicate(TaskId),
+    #[error("Task {task} depends on the unknown task {dependency}")]
+    UnknownDependency { task: TaskId, dependency: TaskId },
+    #[error("Tasks have a cyclic dependency: {}", join_ids(.0))]
+    Cycle(Vec<TaskId>),
+}
+
+fn join_ids(ids: &[TaskId]) -> String {
+    ids.iter().map(TaskId::as_ref).collect::<Vec<_>>().join(" -> ")
+}
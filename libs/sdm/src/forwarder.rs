@@ -31,6 +31,11 @@ use crate::utils::TaskGuard;
 
 pub trait Converter<I, O>: Sync + Send + 'static {
     fn convert(&self, res: Result<I, Error>) -> Option<O>;
+
+    /// An event to send when the stream is over.
+    fn on_end(&self) -> Option<O> {
+        None
+    }
 }
 
 pub struct Forwarder<I, O> {
@@ -66,9 +71,12 @@ where
             if let Some(sdm_event) = self.converter.convert(event) {
                 log::debug!("Sending event: {:?}", sdm_event);
                 if self.sender.send(sdm_event).is_err() {
-                    break;
+                    return;
                 }
             }
         }
+        if let Some(sdm_event) = self.converter.on_end() {
+            self.sender.send(sdm_event).ok();
+        }
     }
 }
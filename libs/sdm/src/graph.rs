@@ -0,0 +1,107 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::collections::HashMap;
+
+use tari_launchpad_protocol::container::TaskId;
+
+pub use crate::error::GraphError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Visiting,
+    Done,
+}
+
+/// Dependencies of the tasks registered in a scope.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// Keeps the order of registration to get a stable result
+    tasks: Vec<(TaskId, Vec<TaskId>)>,
+}
+
+impl DependencyGraph {
+    pub fn add(&mut self, task_id: TaskId, deps: Vec<TaskId>) -> Result<(), GraphError> {
+        if self.tasks.iter().any(|(id, _)| *id == task_id) {
+            return Err(GraphError::Duplicate(task_id));
+        }
+        self.tasks.push((task_id, deps));
+        Ok(())
+    }
+
+    pub fn deps(&self, task_id: &TaskId) -> Option<&[TaskId]> {
+        self.tasks
+            .iter()
+            .find(|(id, _)| id == task_id)
+            .map(|(_, deps)| deps.as_slice())
+    }
+
+    /// Checks that all dependencies are known and don't form a cycle.
+    /// Returns tasks in the topological order: every task follows its dependencies.
+    pub fn resolve(&self) -> Result<Vec<TaskId>, GraphError> {
+        let deps: HashMap<&TaskId, &[TaskId]> = self.tasks.iter().map(|(id, deps)| (id, deps.as_slice())).collect();
+        for (task, task_deps) in &self.tasks {
+            if let Some(dependency) = task_deps.iter().find(|dep| !deps.contains_key(dep)) {
+                return Err(GraphError::UnknownDependency {
+                    task: task.clone(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+        let mut marks = HashMap::new();
+        let mut order = Vec::with_capacity(self.tasks.len());
+        for (task, _) in &self.tasks {
+            let mut path = Vec::new();
+            visit(task, &deps, &mut marks, &mut path, &mut order)?;
+        }
+        Ok(order)
+    }
+}
+
+fn visit<'a>(
+    task: &'a TaskId,
+    deps: &HashMap<&'a TaskId, &'a [TaskId]>,
+    marks: &mut HashMap<&'a TaskId, Mark>,
+    path: &mut Vec<&'a TaskId>,
+    order: &mut Vec<TaskId>,
+) -> Result<(), GraphError> {
+    match marks.get(task) {
+        Some(Mark::Done) => return Ok(()),
+        Some(Mark::Visiting) => {
+            let start = path.iter().position(|id| *id == task).unwrap_or_default();
+            let mut cycle: Vec<TaskId> = path[start..].iter().map(|id| (*id).clone()).collect();
+            cycle.push(task.clone());
+            return Err(GraphError::Cycle(cycle));
+        },
+        None => {},
+    }
+    marks.insert(task, Mark::Visiting);
+    path.push(task);
+    for dep in deps.get(task).copied().unwrap_or_default() {
+        visit(dep, deps, marks, path, order)?;
+    }
+    path.pop();
+    marks.insert(task, Mark::Done);
+    order.push(task.clone());
+    Ok(())
+}
@@ -33,8 +33,12 @@ use tokio::{
     time::{sleep, Duration},
 };
 
-use super::task::Event;
-use crate::{image::ManagedProtocol, scope::ControlEvent, task::TaskSender};
+use super::Event;
+use crate::{
+    image::ManagedProtocol,
+    scope::{ControlEvent, Report},
+    task::TaskSender,
+};
 
 #[derive(Debug)]
 pub enum CheckerEvent {
@@ -66,6 +70,13 @@ impl<P: ManagedProtocol> CheckerContext<P> {
 
         self.sender.send_broadcast(event)
     }
+
+    /// Sends extra details to the owner of the scope.
+    pub fn send_extras(&self, extras: P::Outer) -> Result<(), Error> {
+        let report = Report::Extras(extras);
+
+        self.sender.send_report(report)
+    }
 }
 
 #[async_trait]
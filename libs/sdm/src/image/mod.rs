@@ -24,11 +24,11 @@
 pub mod checker;
 mod task;
 
-use std::fmt;
+use std::{fmt, time::Duration};
 
 use checker::{ContainerChecker, ReadyIfStarted};
 use tari_launchpad_protocol::container::TaskId;
-pub(crate) use task::ImageTask;
+pub(crate) use task::{Event, ImageTask};
 
 use crate::config::ManagedProtocol;
 
@@ -47,6 +47,16 @@ pub trait ManagedContainer: fmt::Debug + Send + 'static {
 
     fn on_event(&mut self, _event: <Self::Protocol as ManagedProtocol>::Inner) {}
 
+    /// The container is disabled by the config and reported as unavailable.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Dependencies that are not required with the current config.
+    fn skipped_deps(&self) -> Vec<TaskId> {
+        Vec::new()
+    }
+
     fn registry(&self) -> &str;
 
     fn image_name(&self) -> &str;
@@ -66,6 +76,55 @@ pub trait ManagedContainer: fmt::Debug + Send + 'static {
     fn volumes(&self, _volumes: &mut Volumes) {}
 
     fn mounts(&self, _mounts: &mut Mounts) {}
+
+    fn timeouts(&self) -> Timeouts {
+        Timeouts::default()
+    }
+}
+
+/// Formats the full reference of the image. The tag could be
+/// a digest (`sha256:...` or `@sha256:...`) to pin the exact image.
+pub fn image_reference(registry: &str, image_name: &str, tag: &str) -> String {
+    let mut reference = String::new();
+    if !registry.is_empty() {
+        reference.push_str(registry);
+        reference.push('/');
+    }
+    reference.push_str(image_name);
+    if tag.starts_with('@') {
+        reference.push_str(tag);
+    } else if tag.starts_with("sha256:") {
+        reference.push('@');
+        reference.push_str(tag);
+    } else {
+        reference.push(':');
+        reference.push_str(tag);
+    }
+    reference
+}
+
+/// Deadlines to receive docker events. The container is inspected
+/// again if the event was not received in time.
+#[derive(Debug, Clone)]
+pub struct Timeouts {
+    /// Time to stop gracefully before the container will be killed.
+    pub stop: Duration,
+    pub killed: Duration,
+    pub removed: Duration,
+    pub created: Duration,
+    pub started: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            stop: Duration::from_secs(10),
+            killed: Duration::from_secs(30),
+            removed: Duration::from_secs(30),
+            created: Duration::from_secs(30),
+            started: Duration::from_secs(60),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -105,13 +164,19 @@ impl Envs {
     }
 }
 
+/// Ports published on the host with the addresses of the host to bind.
 #[derive(Default)]
-pub struct Ports(Vec<u16>);
+pub struct Ports(Vec<(u16, &'static str)>);
 
 impl Ports {
+    /// Publishes the port on all the interfaces of the host.
     pub fn add(&mut self, port: u16) {
-        // let item = (port, port);
-        self.0.push(port);
+        self.0.push((port, ""));
+    }
+
+    /// Publishes the port on the loopback interface of the host only.
+    pub fn add_local(&mut self, port: u16) {
+        self.0.push((port, "127.0.0.1"));
     }
 
     // pub fn forward(&mut self, from: u16, to: u16) {
@@ -119,7 +184,7 @@ impl Ports {
     // self.0.push(item);
     // }
 
-    pub fn build(self) -> Vec<u16> {
+    pub fn build(self) -> Vec<(u16, &'static str)> {
         self.0
     }
 }
@@ -150,6 +215,7 @@ impl Volumes {
     }
 }
 
+#[derive(Debug, Hash)]
 pub enum Mount {
     ToVolume { volume: TaskId, target: String },
     BindTo { source: String, target: String },
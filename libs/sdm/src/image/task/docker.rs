@@ -25,18 +25,7 @@ use std::{collections::HashMap, path::Path};
 
 use anyhow::{anyhow, Error};
 use bollard::{
-    container::{
-        Config,
-        CreateContainerOptions,
-        LogOutput,
-        LogsOptions,
-        NetworkingConfig,
-        RemoveContainerOptions,
-        Stats as BollardStats,
-        StatsOptions,
-    },
-    errors::Error as BollardError,
-    image::{CreateImageOptions, RemoveImageOptions},
+    container::{Config, LogOutput, NetworkingConfig, Stats as BollardStats},
     models::{
         ContainerInspectResponse,
         CreateImageInfo,
@@ -49,10 +38,9 @@ use bollard::{
         PortBinding,
         PortMap,
     },
-    system::EventsOptions,
 };
 use chrono::Local;
-use futures::{StreamExt, TryStreamExt};
+use futures::StreamExt;
 use tari_launchpad_protocol::container::{StatsData, TaskProgress};
 
 use super::{ContainerState, Event, ImageTask};
@@ -77,15 +65,9 @@ use crate::{
 // Container management
 impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     pub fn subscribe_to_events(&mut self) {
-        let mut type_filter = HashMap::new();
-        type_filter.insert("type".to_string(), vec!["container".to_string()]);
-        type_filter.insert("container".to_string(), vec![self.inner.container_name.clone()]);
-        let opts = EventsOptions {
-            since: None,
-            until: None,
-            filters: type_filter,
-        };
-        let stream = self.driver.events(Some(opts)).map_err(Error::from);
+        let stream = self
+            .driver
+            .events(EventMessageTypeEnum::CONTAINER, &self.inner.container_name);
         let sender = self.sender().get_direct().clone();
         let conv = EventConv {
             // TODO: Name is not necessary here
@@ -96,11 +78,11 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     }
 
     pub async fn image_exists(&mut self) -> bool {
-        self.driver.inspect_image(&self.inner.image_name).await.is_ok()
+        self.driver.image_exists(&self.inner.image_name).await
     }
 
     pub async fn container_state(&mut self) -> ContainerState {
-        let res = self.driver.inspect_container(&self.inner.container_name, None).await;
+        let res = self.driver.inspect_container(&self.inner.container_name).await;
         // log::trace!("State of container {}: {:?}", self.inner.container_name, res);
         match res {
             Ok(ContainerInspectResponse { state: Some(state), .. }) => {
@@ -116,40 +98,18 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     }
 
     pub fn pull(&mut self) -> TaskGuard<()> {
-        let opts = Some(CreateImageOptions {
-            from_image: self.inner.image_name.clone(),
-            ..Default::default()
-        });
-        let stream = self.driver.create_image(opts, None, None).map_err(Error::from);
+        let stream = self.driver.pull_image(&self.inner.image_name);
         let sender = self.sender().get_direct().clone();
         Forwarder::start(stream, ProgressConv, sender)
     }
 
     pub fn logs_stream(&mut self) -> Logs {
-        let opts = LogsOptions::<String> {
-            follow: true,
-            stdout: true,
-            stderr: true,
-            ..Default::default()
-        };
-        let stream = self
-            .driver
-            .logs(&self.inner.container_name, Some(opts))
-            .map(log_conv)
-            .map_err(Error::from);
+        let stream = self.driver.logs(&self.inner.container_name).map(log_conv);
         Logs::new(stream)
     }
 
     pub fn stats_stream(&mut self) -> Stats {
-        let opts = StatsOptions {
-            stream: true,
-            one_shot: false,
-        };
-        let stream = self
-            .driver
-            .stats(&self.inner.container_name, Some(opts))
-            .map(stat_conv)
-            .map_err(Error::from);
+        let stream = self.driver.stats(&self.inner.container_name).map(stat_conv);
         Stats::new(stream)
     }
 
@@ -160,9 +120,6 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         self.inner.image.ports(&mut ports);
         let mut envs = Envs::default();
         self.inner.image.envs(&mut envs);
-        let opts = CreateContainerOptions {
-            name: self.inner.container_name.clone(),
-        };
 
         let mut networks = Networks::default();
         self.inner.image.networks(&mut networks);
@@ -198,43 +155,33 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
             networking_config: Some(networks),
             ..Default::default()
         };
-        self.driver.create_container(Some(opts), config).await?;
+        self.driver.create_container(&self.inner.container_name, config).await?;
+        self.inner.applied_spec = Some(self.inner.spec_hash());
         Ok(())
     }
 
     pub async fn try_start_container(&mut self) -> Result<(), Error> {
-        self.driver
-            .start_container::<String>(&self.inner.container_name, None)
-            .await?;
-        Ok(())
+        self.driver.start_container(&self.inner.container_name).await
     }
 
-    pub async fn try_kill_container(&mut self) -> Result<(), Error> {
-        self.driver
-            .kill_container::<String>(&self.inner.container_name, None)
-            .await?;
-        Ok(())
+    /// Sends `SIGTERM` to the container and kills it if it
+    /// wasn't stopped within the timeout.
+    pub async fn try_stop_container(&mut self) -> Result<(), Error> {
+        let timeout = self.inner.image.timeouts().stop;
+        self.driver.stop_container(&self.inner.container_name, timeout).await
+    }
+
+    pub async fn try_restart_container(&mut self) -> Result<(), Error> {
+        let timeout = self.inner.image.timeouts().stop;
+        self.driver.restart_container(&self.inner.container_name, timeout).await
     }
 
     pub async fn try_remove_container(&mut self) -> Result<(), Error> {
-        let opts = RemoveContainerOptions {
-            force: true,
-            ..Default::default()
-        };
-        self.driver
-            .remove_container(&self.inner.container_name, Some(opts))
-            .await?;
-        Ok(())
+        self.driver.remove_container(&self.inner.container_name).await
     }
 
     pub async fn try_remove_image(&mut self) -> Result<(), Error> {
-        let image_name = self.inner.image_name.as_ref();
-        let opts = Some(RemoveImageOptions {
-            force: true,
-            ..Default::default()
-        });
-        self.driver.remove_image(image_name, opts, None).await?;
-        Ok(())
+        self.driver.remove_image(&self.inner.image_name).await
     }
 
     fn networks_map(&self, networks: Networks) -> Result<NetworkingConfig<String>, Error> {
@@ -304,19 +251,20 @@ fn volumes_map(volumes: Vec<String>) -> BollardMap {
     result
 }
 
-fn exposed_ports(ports: &[u16]) -> BollardMap {
+fn exposed_ports(ports: &[(u16, &str)]) -> BollardMap {
     let mut result = BollardMap::new();
-    for port in ports {
+    for (port, _) in ports {
         result.insert(format!("{}/tcp", port), HashMap::default());
     }
     result
 }
 
-fn ports_map(ports: &[u16]) -> PortMap {
+fn ports_map(ports: &[(u16, &str)]) -> PortMap {
     let mut result = PortMap::new();
-    for (k, _) in exposed_ports(ports) {
+    for (port, host_ip) in ports {
+        let k = format!("{}/tcp", port);
         let binding = vec![PortBinding {
-            host_ip: Some(String::new()),
+            host_ip: Some(host_ip.to_string()),
             host_port: Some(k.clone()),
         }];
         result.insert(k, Some(binding));
@@ -324,7 +272,7 @@ fn ports_map(ports: &[u16]) -> PortMap {
     result
 }
 
-fn log_conv(res: Result<LogOutput, BollardError>) -> Result<String, Error> {
+fn log_conv(res: Result<LogOutput, Error>) -> Result<String, Error> {
     if let Ok(LogOutput::Console { message }) = res {
         match std::str::from_utf8(message.as_ref()) {
             Ok(data) => {
@@ -339,7 +287,7 @@ fn log_conv(res: Result<LogOutput, BollardError>) -> Result<String, Error> {
     }
 }
 
-fn stat_conv(res: Result<BollardStats, BollardError>) -> Result<StatsData, Error> {
+fn stat_conv(res: Result<BollardStats, Error>) -> Result<StatsData, Error> {
     if let Ok(BollardStats {
         cpu_stats,
         memory_stats,
@@ -372,6 +320,10 @@ impl Converter<CreateImageInfo, Event> for ProgressConv {
         let progress = TaskProgress { pct: pct as u8, stage };
         Some(Event::PullingProgress(progress))
     }
+
+    fn on_end(&self) -> Option<Event> {
+        Some(Event::PullingFinished)
+    }
 }
 
 struct EventConv {
@@ -393,7 +345,12 @@ impl Converter<EventMessage, Event> for EventConv {
                     if self.name == *name {
                         // TODO: Check the name
                         if let EventMessageTypeEnum::CONTAINER = typ {
-                            return action.try_into().ok();
+                            let event: Event = action.try_into().ok()?;
+                            if let Event::Terminated { .. } = event {
+                                let exit_code = attributes.get("exitCode").and_then(|code| code.parse().ok());
+                                return Some(Event::Terminated { exit_code });
+                            }
+                            return Some(event);
                         }
                     } else {
                         log::error!("Message for other container {}, but expected {}", name, self.name);
@@ -22,7 +22,8 @@
 //
 
 use anyhow::Error;
-use tari_launchpad_protocol::container::{TaskProgress, TaskStatus};
+use chrono::Local;
+use tari_launchpad_protocol::container::{ExitInfo, TaskProgress, TaskStatus};
 
 use super::{Event, ImageTask, Status};
 use crate::{
@@ -36,10 +37,12 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         match event {
             Event::Created => self.on_created(),
             Event::PullingProgress(value) => self.on_pulling_progress(value),
+            Event::PullingFinished => self.on_pulling_finished(),
             Event::Destroyed => self.on_destroyed(),
             Event::Started => self.on_started(),
             Event::Killed => self.on_killed(),
-            Event::Terminated => self.on_terminated(),
+            Event::Terminated { exit_code } => self.on_terminated(exit_code),
+            Event::OutOfMemory => self.on_out_of_memory(),
             Event::CheckerEvent(event) => self.on_checker_event(event),
         }
     }
@@ -58,6 +61,16 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         Ok(())
     }
 
+    fn on_pulling_finished(&mut self) -> Result<(), Error> {
+        // The image is checked by the next update
+        self.status.update(|status| {
+            if let Status::PullingImage { finished, .. } = status {
+                *finished = true;
+            }
+        });
+        Ok(())
+    }
+
     fn on_destroyed(&mut self) -> Result<(), Error> {
         if let Status::WaitContainerRemoved = self.status.get() {
             self.status.set(Status::CleanDangling);
@@ -67,18 +80,23 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
 
     fn on_started(&mut self) -> Result<(), Error> {
         if let Status::WaitContainerStarted { .. } = self.status.get() {
-            let checker = self.inner.image.checker();
-            let logs = self.logs_stream();
-            let stats = self.stats_stream();
-            let sender = self.sender().clone();
-            let context = CheckerContext::new(logs, stats, sender);
-            let fur = checker.entrypoint(context);
-            let checker = tokio::spawn(fur).into();
-            self.status.set(Status::Active { checker, ready: false });
+            self.activate();
         }
         Ok(())
     }
 
+    /// Spawns a checker of the started container.
+    pub(super) fn activate(&mut self) {
+        let checker = self.inner.image.checker();
+        let logs = self.logs_stream();
+        let stats = self.stats_stream();
+        let sender = self.sender().clone();
+        let context = CheckerContext::new(logs, stats, sender);
+        let fur = checker.entrypoint(context);
+        let checker = tokio::spawn(fur).into();
+        self.status.set(Status::Active { checker, ready: false });
+    }
+
     fn on_killed(&mut self) -> Result<(), Error> {
         Ok(())
     }
@@ -102,14 +120,30 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         Ok(())
     }
 
-    fn on_terminated(&mut self) -> Result<(), Error> {
+    fn on_out_of_memory(&mut self) -> Result<(), Error> {
+        if let Status::Active { .. } = self.status.get() {
+            // The `die` event follows
+            self.oom_killed = true;
+        }
+        Ok(())
+    }
+
+    fn on_terminated(&mut self, exit_code: Option<i64>) -> Result<(), Error> {
         match self.status.get() {
             Status::WaitContainerKilled => {
                 self.status.set(Status::CleanDangling);
             },
             Status::Active { .. } => {
-                // TODO: Add waiting interval + fallback
-                // self.status.set(Status::CleanDangling);
+                let info = ExitInfo {
+                    timestamp: Local::now().naive_local(),
+                    exit_code,
+                    oom_killed: std::mem::take(&mut self.oom_killed),
+                };
+                log::warn!("Container {} stopped: {}", self.inner.container_name, info);
+                self.sender().send_exit(info.clone())?;
+                // The restart is scheduled when the container is removed
+                self.exited = Some(info);
+                self.status.set(Status::CleanDangling);
             },
             _ => {},
         }
@@ -25,15 +25,21 @@ mod docker;
 mod events;
 mod update;
 
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
 use anyhow::Error;
 use async_trait::async_trait;
-use tari_launchpad_protocol::container::TaskProgress;
+use tari_launchpad_protocol::container::{ExitInfo, TaskId, TaskProgress};
 
-use super::{checker::CheckerEvent, ManagedContainer};
+use super::{checker::CheckerEvent, image_reference, Args, Envs, ManagedContainer, Mounts, Networks, Ports, Volumes};
 use crate::{
     config::ManagedProtocol,
     error::ParseError,
-    task::{RunnableContext, RunnableTask, TaskContext, TaskEvent, TaskStatusChecker},
+    task::{RunnableContext, RunnableTask, TaskCommand, TaskContext, TaskEvent, TaskStatusChecker},
     utils::TaskGuard,
 };
 
@@ -43,26 +49,64 @@ pub struct ImageTask<C: ManagedProtocol> {
     // TODO: Rename to `fqdn`
     image_name: String,
     image: Box<dyn ManagedContainer<Protocol = C>>,
-    /// A flag to ask to restart a container
-    force_restart: bool,
-    /// A flag to drop and pull image again
-    force_pull: bool,
+    /// An action requested while the container is running
+    pending: Pending,
+    /// A hash of the spec the container was created with
+    applied_spec: Option<u64>,
+    /// The container was killed by the OOM killer
+    oom_killed: bool,
+    /// The container stopped by itself and has to be restarted according to the policy
+    exited: Option<ExitInfo>,
 }
 
 impl<C: ManagedProtocol> ImageTask<C> {
     pub fn new(scope: &str, image: Box<dyn ManagedContainer<Protocol = C>>) -> Self {
         // let required = image.deps().into_iter().collect();
-        let image_name = format!("{}/{}:{}", image.registry(), image.image_name(), image.tag());
+        let image_name = image_reference(image.registry(), image.image_name(), image.tag());
         let container_name = format!("{}_{}", scope, image.image_name());
         Self {
             events: None,
             container_name,
             image_name,
             image,
-            force_restart: false,
-            force_pull: false,
+            pending: Pending::None,
+            applied_spec: None,
+            oom_killed: false,
+            exited: None,
         }
     }
+
+    /// Keeps the strongest of the requested actions,
+    /// e.g. pulling again also recreates the container.
+    fn request(&mut self, action: Pending) {
+        self.pending = self.pending.max(action);
+    }
+
+    /// Calculates a hash of everything the container is created with
+    /// to detect changes that require recreating the container.
+    fn spec_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.image_name.hash(&mut hasher);
+        let mut args = Args::default();
+        self.image.args(&mut args);
+        args.build().hash(&mut hasher);
+        let mut envs = Envs::default();
+        self.image.envs(&mut envs);
+        envs.build().hash(&mut hasher);
+        let mut ports = Ports::default();
+        self.image.ports(&mut ports);
+        ports.build().hash(&mut hasher);
+        let mut networks = Networks::default();
+        self.image.networks(&mut networks);
+        networks.build().hash(&mut hasher);
+        let mut volumes = Volumes::default();
+        self.image.volumes(&mut volumes);
+        volumes.build().hash(&mut hasher);
+        let mut mounts = Mounts::default();
+        self.image.mounts(&mut mounts);
+        mounts.build().hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 #[async_trait]
@@ -78,6 +122,12 @@ impl<C: ManagedProtocol> RunnableTask for ImageTask<C> {
     fn is_permanent(&self) -> bool {
         false
     }
+
+    fn stop_timeout(&self) -> Duration {
+        // The container is killed if it's not stopped in time
+        let timeouts = self.image.timeouts();
+        timeouts.stop + timeouts.killed
+    }
 }
 
 #[async_trait]
@@ -87,17 +137,62 @@ impl<C: ManagedProtocol> RunnableContext<ImageTask<C>> for TaskContext<ImageTask
     }
 
     fn reconfigure(&mut self, config: Option<&C::Config>) -> bool {
-        self.inner.image.reconfigure(config).unwrap_or_default()
+        let active = self.inner.image.reconfigure(config).unwrap_or_default();
+        let image = &self.inner.image;
+        let image_name = image_reference(image.registry(), image.image_name(), image.tag());
+        if image_name != self.inner.image_name {
+            log::info!("The image of {} changed to {}", self.inner.container_name, image_name);
+            self.inner.image_name = image_name;
+            self.inner.request(Pending::ImageChanged);
+            if let Status::PullingImage { .. } = self.status.get() {
+                // Pulling of the previous image is cancelled
+                self.status.set(Status::InitialState);
+            }
+        }
+        // The changed image is a part of the spec as well
+        if let Some(applied_spec) = self.inner.applied_spec {
+            if applied_spec != self.inner.spec_hash() {
+                log::info!("The spec of the container {} changed", self.inner.container_name);
+                self.inner.request(Pending::Restart);
+            }
+        }
+        active
     }
 
     fn process_inner_event(&mut self, event: C::Inner) {
         self.inner.image.on_event(event)
     }
 
+    fn is_available(&self) -> bool {
+        self.inner.image.is_available()
+    }
+
+    fn skipped_dependencies(&self) -> Vec<TaskId> {
+        self.inner.image.skipped_deps()
+    }
+
     fn process_event(&mut self, event: Event) -> Result<(), Error> {
         self.process_event_impl(event)
     }
 
+    fn process_command(&mut self, command: TaskCommand) {
+        match command {
+            TaskCommand::Restart => {
+                // A stopped container will be started again by the runner
+                if let Status::Active { .. } = self.status.get() {
+                    self.status.set(Status::RestartContainer);
+                }
+            },
+            TaskCommand::Stop => {},
+            TaskCommand::Repull => {
+                self.inner.request(Pending::Repull);
+            },
+            TaskCommand::Recreate => {
+                self.inner.request(Pending::Restart);
+            },
+        }
+    }
+
     async fn update(&mut self) -> Result<(), Error> {
         self.process_update_impl().await
     }
@@ -105,16 +200,31 @@ impl<C: ManagedProtocol> RunnableContext<ImageTask<C>> for TaskContext<ImageTask
 
 impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     fn should_be_restarted(&self) -> bool {
-        self.force_restart || self.force_pull
+        self.pending != Pending::None
     }
 }
 
+/// Actions that are applied once the container is stopped.
+/// The order of the variants is the strength of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Pending {
+    None,
+    /// Recreate the container
+    Restart,
+    /// The registry or the tag changed and the image has to be checked again
+    ImageChanged,
+    /// Drop and pull the image again
+    Repull,
+}
+
 #[derive(Debug)]
 pub enum Status {
     InitialState,
 
     PullingImage {
         progress: TaskGuard<()>,
+        /// The stream of the progress is over
+        finished: bool,
     },
 
     CleanDangling,
@@ -127,6 +237,11 @@ pub enum Status {
     StartContainer,
     WaitContainerStarted,
 
+    /// An expected event was not received in time
+    InspectContainer {
+        waited_for: WaitFor,
+    },
+
     /// Check the `active` flag
     Idle,
 
@@ -135,6 +250,9 @@ pub enum Status {
         ready: bool,
     },
 
+    /// Restarts the process of the running container
+    RestartContainer,
+
     DropImage,
 }
 
@@ -142,6 +260,10 @@ impl TaskStatusChecker for Status {
     fn is_ready(&self) -> bool {
         matches!(self, Self::Active { ready: true, .. })
     }
+
+    fn is_down(&self) -> bool {
+        matches!(self, Self::InitialState | Self::PullingImage { .. } | Self::Idle)
+    }
 }
 
 impl Default for Status {
@@ -150,6 +272,14 @@ impl Default for Status {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum WaitFor {
+    Killed,
+    Removed,
+    Created,
+    Started,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ContainerState {
     Running,
@@ -161,10 +291,12 @@ pub enum ContainerState {
 pub enum Event {
     Destroyed,
     PullingProgress(TaskProgress),
+    PullingFinished,
     Created,
     Started,
     Killed,
-    Terminated,
+    Terminated { exit_code: Option<i64> },
+    OutOfMemory,
     CheckerEvent(CheckerEvent),
 }
 
@@ -180,7 +312,8 @@ impl TryFrom<String> for Event {
             "create" => Ok(Self::Created),
             "start" => Ok(Self::Started),
             "kill" => Ok(Self::Killed),
-            "die" => Ok(Self::Terminated),
+            "die" => Ok(Self::Terminated { exit_code: None }),
+            "oom" => Ok(Self::OutOfMemory),
             _ => Err(ParseError(value)),
         }
     }
@@ -21,10 +21,10 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use anyhow::Error;
+use anyhow::{anyhow, Error};
 use tari_launchpad_protocol::container::{TaskProgress, TaskStatus};
 
-use super::{ContainerState, ImageTask, Status};
+use super::{ContainerState, ImageTask, Pending, Status, WaitFor};
 use crate::{config::ManagedProtocol, task::TaskContext};
 
 impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
@@ -40,13 +40,22 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
             Status::WaitContainerCreated => self.do_wait_container_created().await,
             Status::StartContainer => self.do_start_container().await,
             Status::WaitContainerStarted => self.do_wait_container_started().await,
+            Status::InspectContainer { waited_for } => {
+                let waited_for = *waited_for;
+                self.do_inspect_container(waited_for).await
+            },
             Status::Active { .. } => self.do_active().await,
+            Status::RestartContainer => self.do_restart_container().await,
             Status::DropImage => self.do_drop_image().await,
         }
     }
 
     async fn do_initial_state(&mut self) -> Result<(), Error> {
         self.update_task_status(TaskStatus::Inactive)?;
+        if !self.is_available() {
+            // The image of a disabled container is pulled once the config enables it
+            return Ok(());
+        }
 
         log::debug!("Cheking image {} ...", self.inner.image_name);
         if self.image_exists().await {
@@ -70,17 +79,28 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         let progress = TaskProgress::new("Pulling...");
         self.update_task_status(TaskStatus::Progress(progress))?;
         let progress = self.pull();
-        self.status.set(Status::PullingImage { progress });
+        self.status.set(Status::PullingImage {
+            progress,
+            finished: false,
+        });
         Ok(())
     }
 
     async fn do_pulling(&mut self) -> Result<(), Error> {
+        // The image is inspected once the stream of the progress is over,
+        // not on every event of the progress
+        if let Status::PullingImage { finished: false, .. } = self.status.get() {
+            return Ok(());
+        }
         if self.image_exists().await {
             // Just loaded, container can't be exist
             self.status.set(Status::Idle);
             self.update_task_status(TaskStatus::Inactive)?;
+            Ok(())
+        } else {
+            self.status.set(Status::InitialState);
+            Err(anyhow!("Can't pull the image {}", self.inner.image_name))
         }
-        Ok(())
     }
 
     async fn do_clean_dangling(&mut self) -> Result<(), Error> {
@@ -88,14 +108,14 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         let state = self.container_state().await;
         match state {
             ContainerState::Running => {
-                log::debug!("Container {} is running. Terminating it.", self.inner.container_name);
-                self.try_kill_container().await?;
-                self.status.set(Status::WaitContainerKilled);
+                log::debug!("Container {} is running. Stopping it.", self.inner.container_name);
+                self.try_stop_container().await?;
+                self.wait_for(WaitFor::Killed);
             },
             ContainerState::NotRunning => {
                 log::debug!("Container {} is not running. Removing it.", self.inner.container_name);
                 self.try_remove_container().await?;
-                self.status.set(Status::WaitContainerRemoved);
+                self.wait_for(WaitFor::Removed);
             },
             ContainerState::NotFound => {
                 log::debug!("Container {} doesn't exist.", self.inner.container_name);
@@ -107,24 +127,86 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     }
 
     async fn do_wait_container_killed(&mut self) -> Result<(), Error> {
-        // TODO: Wait interval
         Ok(())
     }
 
     async fn do_wait_container_removed(&mut self) -> Result<(), Error> {
-        // TODO: Wait interval
         Ok(())
     }
 
+    /// Sets a waiting status that falls back to inspecting
+    /// the container if an event won't be received in time.
+    fn wait_for(&mut self, waited_for: WaitFor) {
+        let timeouts = self.inner.image.timeouts();
+        let (status, timeout) = match waited_for {
+            WaitFor::Killed => (Status::WaitContainerKilled, timeouts.killed),
+            WaitFor::Removed => (Status::WaitContainerRemoved, timeouts.removed),
+            WaitFor::Created => (Status::WaitContainerCreated, timeouts.created),
+            WaitFor::Started => (Status::WaitContainerStarted, timeouts.started),
+        };
+        let next_status = Status::InspectContainer { waited_for };
+        self.status.set_with_fallback(status, timeout, next_status);
+    }
+
+    async fn do_inspect_container(&mut self, waited_for: WaitFor) -> Result<(), Error> {
+        let state = self.container_state().await;
+        log::warn!(
+            "No event from the container {} for {:?}. The container is {:?}",
+            self.inner.container_name,
+            waited_for,
+            state
+        );
+        match (waited_for, state) {
+            (WaitFor::Killed, ContainerState::NotRunning | ContainerState::NotFound) |
+            (WaitFor::Removed, ContainerState::NotFound) => {
+                self.status.set(Status::CleanDangling);
+                Ok(())
+            },
+            (WaitFor::Created, ContainerState::NotRunning) => {
+                self.status.set(Status::StartContainer);
+                Ok(())
+            },
+            (WaitFor::Created | WaitFor::Started, ContainerState::Running) => {
+                self.activate();
+                Ok(())
+            },
+            (WaitFor::Created, ContainerState::NotFound) => {
+                self.status.set(Status::CreateContainer);
+                Err(anyhow!("Container {} was not created", self.inner.container_name))
+            },
+            (WaitFor::Started, ContainerState::NotRunning) => {
+                self.status.set(Status::StartContainer);
+                Err(anyhow!("Container {} was not started", self.inner.container_name))
+            },
+            (waited_for, state) => {
+                self.status.set(Status::CleanDangling);
+                Err(anyhow!(
+                    "Container {} is {:?} after waiting for {:?}",
+                    self.inner.container_name,
+                    state,
+                    waited_for
+                ))
+            },
+        }
+    }
+
     async fn do_idle(&mut self) -> Result<(), Error> {
-        if self.force_pull {
-            self.force_pull = false;
+        if let Some(info) = self.exited.take() {
+            // The runner waits for the next attempt
+            self.report_exit(info)
+        } else if self.pending == Pending::ImageChanged {
+            self.pending = Pending::None;
+            // Pulls the image if it doesn't exist
+            self.status.set(Status::InitialState);
+            Ok(())
+        } else if self.pending == Pending::Repull {
+            self.pending = Pending::None;
             self.status.set(Status::DropImage);
             let progress = TaskProgress::new("Removing image...");
             self.update_task_status(TaskStatus::Progress(progress))?;
             Ok(())
         } else if self.should_be_active() {
-            self.force_restart = false;
+            self.pending = Pending::None;
             log::debug!("Preparing a container {} to start...", self.inner.container_name);
             self.status.set(Status::CreateContainer);
             self.update_task_status(TaskStatus::Pending)?;
@@ -137,18 +219,17 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
     async fn do_create_container(&mut self) -> Result<(), Error> {
         log::debug!("Trying to create container {} ...", self.inner.container_name);
         self.try_create_container().await?;
-        self.status.set(Status::WaitContainerCreated);
+        self.wait_for(WaitFor::Created);
         Ok(())
     }
 
     async fn do_wait_container_created(&mut self) -> Result<(), Error> {
-        // TODO: Check timeout
         Ok(())
     }
 
     async fn do_start_container(&mut self) -> Result<(), Error> {
         self.try_start_container().await?;
-        self.status.set(Status::WaitContainerStarted);
+        self.wait_for(WaitFor::Started);
         self.update_task_status(TaskStatus::Pending)?;
         Ok(())
     }
@@ -164,7 +245,19 @@ impl<C: ManagedProtocol> TaskContext<ImageTask<C>> {
         Ok(())
     }
 
+    async fn do_restart_container(&mut self) -> Result<(), Error> {
+        log::debug!("Restarting container {} ...", self.inner.container_name);
+        self.try_restart_container().await?;
+        // Events of the restart are ignored until the container is started
+        self.wait_for(WaitFor::Started);
+        self.update_task_status(TaskStatus::Pending)?;
+        Ok(())
+    }
+
     async fn do_drop_image(&mut self) -> Result<(), Error> {
-        self.try_remove_image().await
+        self.try_remove_image().await?;
+        // Pulls the image again
+        self.status.set(Status::InitialState);
+        Ok(())
     }
 }
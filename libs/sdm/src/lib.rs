@@ -24,15 +24,19 @@
 pub mod config;
 mod error;
 mod forwarder;
+pub mod graph;
 pub mod image;
 pub mod network;
+pub mod policy;
+pub mod process;
+pub mod runtime;
 mod scope;
-mod status;
-mod task;
+pub mod status;
+pub mod task;
 pub mod utils;
 pub mod volume;
 
-pub use scope::{Report, ReportEnvelope, SdmScope};
+pub use scope::{ControlEvent, Report, ReportEnvelope, SdmScope, TaskHandle};
 
 // Is temporary mod
 pub mod ids {
@@ -21,15 +21,8 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::collections::HashMap;
-
 use anyhow::Error;
-use bollard::{
-    models::{EventMessage, EventMessageTypeEnum},
-    network::{CreateNetworkOptions, InspectNetworkOptions},
-    system::EventsOptions,
-};
-use futures::TryStreamExt;
+use bollard::models::{EventMessage, EventMessageTypeEnum};
 
 use super::{Event, NetworkTask};
 use crate::{
@@ -40,15 +33,9 @@ use crate::{
 
 impl<C: ManagedProtocol> TaskContext<NetworkTask<C>> {
     pub fn subscribe_to_events(&mut self) {
-        let mut type_filter = HashMap::new();
-        type_filter.insert("type".to_string(), vec!["network".to_string()]);
-        type_filter.insert("network".to_string(), vec![self.inner.network_name.clone()]);
-        let opts = EventsOptions {
-            since: None,
-            until: None,
-            filters: type_filter,
-        };
-        let stream = self.driver.events(Some(opts)).map_err(Error::from);
+        let stream = self
+            .driver
+            .events(EventMessageTypeEnum::NETWORK, &self.inner.network_name);
         let sender = self.sender().get_direct().clone();
         let conv = EventConv {
             // TODO: Name is not necessary here
@@ -59,37 +46,15 @@ impl<C: ManagedProtocol> TaskContext<NetworkTask<C>> {
     }
 
     pub async fn network_exists(&mut self) -> bool {
-        let opts = InspectNetworkOptions {
-            verbose: false,
-            scope: "local",
-        };
-        self.driver
-            .inspect_network(&self.inner.network_name, Some(opts))
-            .await
-            .is_ok()
+        self.driver.network_exists(&self.inner.network_name).await
     }
 
     pub async fn try_create_network(&mut self) -> Result<(), Error> {
-        let options = CreateNetworkOptions {
-            name: self.inner.network_name.as_ref(),
-            check_duplicate: true,
-            driver: "bridge",
-            internal: false,
-            attachable: false,
-            ingress: false,
-            ipam: Default::default(),
-            enable_ipv6: false,
-            options: Default::default(),
-            labels: Default::default(),
-        };
-        self.driver.create_network(options).await?;
-        // TODO: Check warnings...
-        Ok(())
+        self.driver.create_network(&self.inner.network_name).await
     }
 
     pub async fn try_remove_network(&mut self) -> Result<(), Error> {
-        self.driver.remove_network(&self.inner.network_name).await?;
-        Ok(())
+        self.driver.remove_network(&self.inner.network_name).await
     }
 }
 
@@ -105,6 +105,10 @@ impl TaskStatusChecker for Status {
     fn is_ready(&self) -> bool {
         matches!(self, Self::Active)
     }
+
+    fn is_down(&self) -> bool {
+        matches!(self, Self::InitialState | Self::Inactive)
+    }
 }
 
 impl Default for Status {
@@ -0,0 +1,165 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use tokio::time::{Duration, Instant};
+
+/// Exponential delays between restart attempts.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// The delay before the first restart.
+    pub initial: Duration,
+    /// The upper bound of the delay.
+    pub max: Duration,
+    /// The task is not restarted anymore after that number of failures.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(2),
+            max: Duration::from_secs(60),
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Returns a delay before the attempt with the given number (starts from `1`).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+
+    fn allows(&self, attempts: u32) -> bool {
+        self.max_attempts.map(|max| attempts < max).unwrap_or(true)
+    }
+}
+
+/// Declares what to do when a task fails.
+#[derive(Debug, Clone)]
+pub enum RestartPolicy {
+    /// Leave the task failed until the config changes.
+    Never,
+    /// Restart the task after failures only.
+    OnFailure(Backoff),
+    /// Restart the task every time it stopped unexpectedly,
+    /// even if it has exited successfully.
+    Always(Backoff),
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::OnFailure(Backoff::default())
+    }
+}
+
+impl RestartPolicy {
+    fn backoff(&self) -> Option<&Backoff> {
+        match self {
+            Self::Never => None,
+            Self::OnFailure(backoff) | Self::Always(backoff) => Some(backoff),
+        }
+    }
+}
+
+/// Counts failures of a task and schedules restarts according to the policy.
+#[derive(Debug)]
+pub struct Restarts {
+    policy: RestartPolicy,
+    attempts: u32,
+    next_retry: Option<Instant>,
+    halted: bool,
+}
+
+impl Restarts {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            attempts: 0,
+            next_retry: None,
+            halted: false,
+        }
+    }
+
+    pub fn policy(&self) -> &RestartPolicy {
+        &self.policy
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn next_retry(&self) -> Option<Instant> {
+        self.next_retry
+    }
+
+    /// The task gave up and waits for a reconfiguration.
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Registers a failure and returns a delay before the next attempt
+    /// or `None` if the task should not be restarted.
+    pub fn register_failure(&mut self) -> Option<Duration> {
+        self.attempts = self.attempts.saturating_add(1);
+        let delay = self
+            .policy
+            .backoff()
+            .filter(|backoff| backoff.allows(self.attempts))
+            .map(|backoff| backoff.delay(self.attempts));
+        self.next_retry = delay.map(|delay| Instant::now() + delay);
+        self.halted = delay.is_none();
+        delay
+    }
+
+    /// Registers an exit of the task. Successful exits are restarted
+    /// by the `Always` policy only.
+    pub fn register_exit(&mut self, success: bool) -> Option<Duration> {
+        if success && !matches!(self.policy, RestartPolicy::Always(_)) {
+            self.next_retry = None;
+            self.halted = true;
+            None
+        } else {
+            self.register_failure()
+        }
+    }
+
+    /// The task waits for the next attempt.
+    pub fn is_waiting(&self) -> bool {
+        self.halted || self.next_retry.map(|next| next > Instant::now()).unwrap_or(false)
+    }
+
+    /// Lets a halted task try again, but keeps the counter.
+    pub fn resume(&mut self) {
+        self.halted = false;
+        self.next_retry = None;
+    }
+
+    /// Called when the task started successfully.
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.next_retry = None;
+        self.halted = false;
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+mod task;
+
+use std::path::PathBuf;
+
+use tari_launchpad_protocol::container::TaskId;
+pub(crate) use task::ProcessTask;
+
+use crate::image::ManagedContainer;
+
+/// A container that can be started as a local child process
+/// with the same arguments, environment variables and mounts.
+/// Ports and networks are not used, since the process runs on the host.
+pub trait ManagedProcess: ManagedContainer {
+    /// A path to the binary. The binary with the name of the image
+    /// is searched in `PATH` by default.
+    fn program(&self) -> PathBuf {
+        self.image_name().into()
+    }
+
+    /// A directory on the host that replaces the volume.
+    /// Paths of mounts are replaced in arguments and environment variables.
+    fn volume_dir(&self, _volume: &TaskId) -> Option<PathBuf> {
+        None
+    }
+
+    /// A path to the file that is sent to the standard input.
+    /// The path is mapped like paths in arguments.
+    fn stdin(&self) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use anyhow::Error;
+use chrono::Local;
+use tari_launchpad_protocol::container::{ExitInfo, TaskStatus};
+
+use super::{ProcessTask, Status};
+use crate::{
+    config::ManagedProtocol,
+    image::{
+        checker::{CheckerContext, CheckerEvent},
+        Event,
+    },
+    task::TaskContext,
+};
+
+impl<C: ManagedProtocol> TaskContext<ProcessTask<C>> {
+    pub fn process_event_impl(&mut self, event: Event) -> Result<(), Error> {
+        match event {
+            Event::Terminated { exit_code } => self.on_terminated(exit_code),
+            Event::CheckerEvent(event) => self.on_checker_event(event),
+            // Other events are produced by containers only
+            _ => Ok(()),
+        }
+    }
+
+    /// Spawns a checker of the started process.
+    pub(super) fn activate(&mut self) {
+        let checker = self.inner.process.checker();
+        let logs = self.logs_stream();
+        let stats = self.stats_stream();
+        let sender = self.sender().clone();
+        let context = CheckerContext::new(logs, stats, sender);
+        let fur = checker.entrypoint(context);
+        let checker = tokio::spawn(fur).into();
+        self.status.set(Status::Active { checker, ready: false });
+    }
+
+    fn on_checker_event(&mut self, event: CheckerEvent) -> Result<(), Error> {
+        if let Status::Active { .. } = self.status.get() {
+            match event {
+                CheckerEvent::Progress(progress) => {
+                    self.update_task_status(TaskStatus::Progress(progress))?;
+                },
+                CheckerEvent::Ready => {
+                    self.status.update(|status| {
+                        if let Status::Active { ready, .. } = status {
+                            *ready = true;
+                        }
+                    });
+                    self.update_task_status(TaskStatus::Active)?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    fn on_terminated(&mut self, exit_code: Option<i64>) -> Result<(), Error> {
+        self.child = None;
+        match self.status.get() {
+            Status::Active { .. } => {
+                let info = ExitInfo {
+                    timestamp: Local::now().naive_local(),
+                    exit_code,
+                    oom_killed: false,
+                };
+                log::warn!("Process {} stopped: {}", self.inner.process_name, info);
+                self.sender().send_exit(info.clone())?;
+                self.exited = Some(info);
+            },
+            _ => {
+                self.update_task_status(TaskStatus::Inactive)?;
+            },
+        }
+        self.status.set(Status::Idle);
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+mod events;
+mod native;
+mod update;
+
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use tari_launchpad_protocol::container::{ExitInfo, TaskId};
+use tokio::sync::mpsc;
+
+use super::ManagedProcess;
+use crate::{
+    config::ManagedProtocol,
+    image::{checker::Logs, Args, Envs, Event, Mounts},
+    task::{RunnableContext, RunnableTask, TaskCommand, TaskContext, TaskStatusChecker},
+    utils::TaskGuard,
+};
+
+pub struct ProcessTask<C: ManagedProtocol> {
+    process_name: String,
+    process: Box<dyn ManagedProcess<Protocol = C>>,
+    /// The running child process
+    child: Option<Child>,
+    /// A flag to ask to restart a process
+    force_restart: bool,
+    /// A hash of the spec the process was started with
+    applied_spec: Option<u64>,
+    /// The process stopped by itself and has to be restarted according to the policy
+    exited: Option<ExitInfo>,
+}
+
+impl<C: ManagedProtocol> ProcessTask<C> {
+    pub fn new(scope: &str, process: Box<dyn ManagedProcess<Protocol = C>>) -> Self {
+        let process_name = format!("{}_{}", scope, process.image_name());
+        Self {
+            process_name,
+            process,
+            child: None,
+            force_restart: false,
+            applied_spec: None,
+            exited: None,
+        }
+    }
+
+    /// Calculates a hash of everything the process is started with
+    /// to detect changes that require restarting the process.
+    fn spec_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.process.program().hash(&mut hasher);
+        let mut args = Args::default();
+        self.process.args(&mut args);
+        args.build().hash(&mut hasher);
+        let mut envs = Envs::default();
+        self.process.envs(&mut envs);
+        envs.build().hash(&mut hasher);
+        let mut mounts = Mounts::default();
+        self.process.mounts(&mut mounts);
+        mounts.build().hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// A handle of the spawned process.
+/// The process is killed if the handle is dropped.
+struct Child {
+    /// Used to read the stats only, signals are sent by the supervisor
+    pid: Option<u32>,
+    /// Output of the process until a checker takes it
+    logs: Option<Logs>,
+    signal_tx: mpsc::UnboundedSender<Signal>,
+    _supervisor: TaskGuard<()>,
+}
+
+/// A request to the supervisor that owns the process.
+#[derive(Debug, Clone, Copy)]
+enum Signal {
+    Terminate,
+    Kill,
+}
+
+#[async_trait]
+impl<C: ManagedProtocol> RunnableTask for ProcessTask<C> {
+    // Events of containers are reused to share checkers
+    type Event = Event;
+    type Protocol = C;
+    type Status = Status;
+
+    fn name(&self) -> &str {
+        self.process_name.as_ref()
+    }
+
+    fn is_permanent(&self) -> bool {
+        false
+    }
+
+    fn stop_timeout(&self) -> Duration {
+        self.process.timeouts().stop
+    }
+}
+
+#[async_trait]
+impl<C: ManagedProtocol> RunnableContext<ProcessTask<C>> for TaskContext<ProcessTask<C>> {
+    async fn initialize(&mut self) {}
+
+    fn reconfigure(&mut self, config: Option<&C::Config>) -> bool {
+        let active = self.inner.process.reconfigure(config).unwrap_or_default();
+        if let Some(applied_spec) = self.inner.applied_spec {
+            if applied_spec != self.inner.spec_hash() {
+                log::info!("The spec of the process {} changed", self.inner.process_name);
+                self.force_restart = true;
+            }
+        }
+        active
+    }
+
+    fn process_inner_event(&mut self, event: C::Inner) {
+        self.inner.process.on_event(event)
+    }
+
+    fn is_available(&self) -> bool {
+        self.inner.process.is_available()
+    }
+
+    fn skipped_dependencies(&self) -> Vec<TaskId> {
+        self.inner.process.skipped_deps()
+    }
+
+    fn process_event(&mut self, event: Event) -> Result<(), Error> {
+        self.process_event_impl(event)
+    }
+
+    fn process_command(&mut self, command: TaskCommand) {
+        match command {
+            TaskCommand::Restart | TaskCommand::Recreate => {
+                self.force_restart = true;
+            },
+            TaskCommand::Stop => {},
+            TaskCommand::Repull => {
+                log::warn!("The process {} has no image to pull", self.inner.process_name);
+            },
+        }
+    }
+
+    async fn update(&mut self) -> Result<(), Error> {
+        self.process_update_impl().await
+    }
+}
+
+#[derive(Debug)]
+pub enum Status {
+    /// Check the `active` flag
+    Idle,
+
+    Active {
+        checker: TaskGuard<()>,
+        ready: bool,
+    },
+
+    /// Waits for the process to exit gracefully
+    WaitProcessStopped,
+    /// The process was not stopped in time
+    KillProcess,
+    WaitProcessKilled,
+}
+
+impl TaskStatusChecker for Status {
+    fn is_ready(&self) -> bool {
+        matches!(self, Self::Active { ready: true, .. })
+    }
+
+    fn is_down(&self) -> bool {
+        matches!(self, Self::Idle)
+    }
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{fs, process::Stdio};
+
+use anyhow::{anyhow, Error};
+use chrono::Local;
+use futures::{stream, StreamExt};
+use tari_launchpad_protocol::container::StatsData;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::{Child as ChildProcess, Command},
+    select,
+    sync::mpsc,
+    time::{interval, Duration},
+};
+use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
+
+use super::{Child, ProcessTask, Signal};
+use crate::{
+    config::ManagedProtocol,
+    image::{
+        checker::{Logs, Stats},
+        Args,
+        Envs,
+        Event,
+        Mount,
+        Mounts,
+    },
+    task::{TaskContext, TaskSender},
+};
+
+/// Ticks of the kernel clock per second used by `/proc` counters.
+const CLOCK_TICKS: i64 = 100;
+
+// Process management
+impl<C: ManagedProtocol> TaskContext<ProcessTask<C>> {
+    pub fn try_spawn_process(&mut self) -> Result<(), Error> {
+        let paths = self.paths_map()?;
+        let mut args = Args::default();
+        self.inner.process.args(&mut args);
+        let mut envs = Envs::default();
+        self.inner.process.envs(&mut envs);
+
+        let mut command = Command::new(self.inner.process.program());
+        for arg in args.build() {
+            command.arg(map_paths(&arg, &paths));
+        }
+        for env in envs.build() {
+            let (name, value) = env
+                .split_once('=')
+                .ok_or_else(|| anyhow!("Invalid environment variable: {}", env))?;
+            command.env(name, map_paths(value, &paths));
+        }
+        let stdin = match self.inner.process.stdin() {
+            Some(path) => fs::File::open(map_paths(&path, &paths))?.into(),
+            None => Stdio::null(),
+        };
+        command
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = command
+            .spawn()
+            .map_err(|err| anyhow!("Can't spawn the process {}: {}", self.inner.process_name, err))?;
+        log::debug!("Process {} spawned with pid {:?}", self.inner.process_name, child.id());
+
+        let (logs_tx, logs_rx) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(read_lines(stdout, logs_tx.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(read_lines(stderr, logs_tx));
+        }
+        let (signal_tx, signal_rx) = mpsc::unbounded_channel();
+        let pid = child.id();
+        let supervisor = tokio::spawn(supervise(child, signal_rx, self.sender().clone())).into();
+        self.inner.child = Some(Child {
+            pid,
+            logs: Some(Logs::new(UnboundedReceiverStream::new(logs_rx))),
+            signal_tx,
+            _supervisor: supervisor,
+        });
+        self.inner.applied_spec = Some(self.inner.spec_hash());
+        Ok(())
+    }
+
+    /// Asks the process to exit gracefully.
+    pub fn stop_process(&mut self) {
+        self.signal(Signal::Terminate);
+    }
+
+    pub fn kill_process(&mut self) {
+        self.signal(Signal::Kill);
+    }
+
+    fn signal(&self, signal: Signal) {
+        if let Some(child) = self.inner.child.as_ref() {
+            // The supervisor has gone if the process exited
+            child.signal_tx.send(signal).ok();
+        }
+    }
+
+    pub fn logs_stream(&mut self) -> Logs {
+        self.inner
+            .child
+            .as_mut()
+            .and_then(|child| child.logs.take())
+            .unwrap_or_else(|| Logs::new(stream::empty()))
+    }
+
+    pub fn stats_stream(&mut self) -> Stats {
+        let pid = self.inner.child.as_ref().and_then(|child| child.pid);
+        let stream = IntervalStream::new(interval(Duration::from_secs(1))).map(move |_| {
+            let pid = pid.ok_or_else(|| anyhow!("The process has no pid"))?;
+            read_stats(pid)
+        });
+        Stats::new(stream)
+    }
+
+    /// Maps paths inside containers to directories on the host.
+    fn paths_map(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut mounts = Mounts::default();
+        self.inner.process.mounts(&mut mounts);
+        let mut paths = Vec::new();
+        for mount in mounts.build() {
+            match mount {
+                Mount::BindTo { source, target } => {
+                    paths.push((target, source));
+                },
+                Mount::ToVolume { volume, target } => {
+                    let dir = self
+                        .inner
+                        .process
+                        .volume_dir(&volume)
+                        .ok_or_else(|| anyhow!("No directory for the volume {} of a process", volume))?;
+                    fs::create_dir_all(&dir)?;
+                    paths.push((target, dir.display().to_string()));
+                },
+            }
+        }
+        // Nested paths are replaced first
+        paths.sort_by(|(left, _), (right, _)| right.len().cmp(&left.len()));
+        Ok(paths)
+    }
+}
+
+/// Replaces a path inside a container with the path on the host.
+fn map_paths(value: &str, paths: &[(String, String)]) -> String {
+    for (target, source) in paths {
+        if let Some(pos) = value.find(target.as_str()) {
+            let rest = &value[pos + target.len()..];
+            if rest.is_empty() || rest.starts_with('/') {
+                return format!("{}{}{}", &value[..pos], source, rest);
+            }
+        }
+    }
+    value.to_string()
+}
+
+/// Asks the process to exit gracefully.
+/// The pid is taken from the handle that wasn't waited yet,
+/// so it can't belong to another process.
+#[cfg(unix)]
+fn terminate(child: &ChildProcess) -> bool {
+    match child.id() {
+        // SAFETY: The child isn't reaped, so the pid is still reserved for it
+        Some(pid) => unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) == 0 },
+        None => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(_child: &ChildProcess) -> bool {
+    false
+}
+
+async fn read_lines<R>(reader: R, tx: mpsc::UnboundedSender<Result<String, Error>>)
+where R: AsyncRead + Unpin {
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => {
+                if tx.send(Ok(line)).is_err() {
+                    break;
+                }
+            },
+            Ok(None) => break,
+            Err(err) => {
+                tx.send(Err(err.into())).ok();
+                break;
+            },
+        }
+    }
+}
+
+/// Waits for the exit of the process, delivers signals to it
+/// and reports the exit to the task.
+async fn supervise<P: ManagedProtocol>(
+    mut child: ChildProcess,
+    mut signal_rx: mpsc::UnboundedReceiver<Signal>,
+    sender: TaskSender<Event, P>,
+) {
+    let status = loop {
+        select! {
+            status = child.wait() => break status,
+            signal = signal_rx.recv() => {
+                match signal {
+                    Some(Signal::Terminate) if terminate(&child) => {},
+                    // The handle was dropped or the process has to be killed
+                    Some(Signal::Terminate | Signal::Kill) | None => {
+                        child.start_kill().ok();
+                        break child.wait().await;
+                    },
+                }
+            }
+        }
+    };
+    let exit_code = status.ok().and_then(|status| status.code()).map(i64::from);
+    sender.send_direct(Event::Terminated { exit_code }).ok();
+}
+
+/// Reads usage of resources of the process from `/proc`.
+fn read_stats(pid: u32) -> Result<StatsData, Error> {
+    // Fields after the name of the process in `/proc/<pid>/stat`
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let fields: Vec<&str> = stat
+        .rsplit_once(')')
+        .map(|(_, rest)| rest.split_whitespace().collect())
+        .unwrap_or_default();
+    let ticks = |idx: usize| -> Result<i64, Error> {
+        let value = fields
+            .get(idx)
+            .ok_or_else(|| anyhow!("No field {} in the stat of {}", idx, pid))?;
+        Ok(value.parse()?)
+    };
+    // `utime` and `stime`
+    let cpu_usage = ticks(11)? + ticks(12)?;
+
+    let system = fs::read_to_string("/proc/stat")?;
+    let system_cpu_usage = system
+        .lines()
+        .next()
+        .ok_or_else(|| anyhow!("No cpu line in /proc/stat"))?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|value| value.parse::<i64>().ok())
+        .sum::<i64>();
+
+    let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+    let mem_usage = read_kb(&status, "VmRSS:")?;
+    let meminfo = fs::read_to_string("/proc/meminfo")?;
+    let mem_limit = read_kb(&meminfo, "MemTotal:")?;
+
+    // Counters are converted to nanoseconds as docker provides
+    Ok(StatsData {
+        timestamp: Local::now().naive_local(),
+        system_cpu_usage: system_cpu_usage * 1_000_000_000 / CLOCK_TICKS,
+        cpu_usage: cpu_usage * 1_000_000_000 / CLOCK_TICKS,
+        mem_limit: mem_limit.into(),
+        mem_usage: mem_usage.into(),
+    })
+}
+
+/// Reads a value in kilobytes from `/proc` and returns it in bytes.
+fn read_kb(content: &str, key: &str) -> Result<u64, Error> {
+    let value = content
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|value| value.split_whitespace().next())
+        .ok_or_else(|| anyhow!("No {} in /proc", key))?;
+    Ok(value.parse::<u64>()? * 1024)
+}
@@ -0,0 +1,87 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use anyhow::Error;
+use tari_launchpad_protocol::container::{TaskProgress, TaskStatus};
+
+use super::{ProcessTask, Status};
+use crate::{config::ManagedProtocol, task::TaskContext};
+
+impl<C: ManagedProtocol> TaskContext<ProcessTask<C>> {
+    pub async fn process_update_impl(&mut self) -> Result<(), Error> {
+        match self.status.get() {
+            Status::Idle => self.do_idle().await,
+            Status::Active { .. } => self.do_active().await,
+            Status::WaitProcessStopped => self.do_wait_process_stopped().await,
+            Status::KillProcess => self.do_kill_process().await,
+            Status::WaitProcessKilled => self.do_wait_process_killed().await,
+        }
+    }
+
+    async fn do_idle(&mut self) -> Result<(), Error> {
+        if let Some(info) = self.exited.take() {
+            // The runner waits for the next attempt
+            self.report_exit(info)
+        } else if self.should_be_active() {
+            self.force_restart = false;
+            log::debug!("Starting a process {} ...", self.inner.process_name);
+            self.update_task_status(TaskStatus::Pending)?;
+            self.try_spawn_process()?;
+            self.activate();
+            Ok(())
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn do_active(&mut self) -> Result<(), Error> {
+        if !self.should_be_active() || self.force_restart {
+            log::debug!("Stopping the process {} ...", self.inner.process_name);
+            let progress = TaskProgress::new("Stopping...");
+            self.update_task_status(TaskStatus::Progress(progress))?;
+            self.stop_process();
+            let timeout = self.inner.process.timeouts().stop;
+            self.status
+                .set_with_fallback(Status::WaitProcessStopped, timeout, Status::KillProcess);
+        }
+        Ok(())
+    }
+
+    async fn do_wait_process_stopped(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn do_kill_process(&mut self) -> Result<(), Error> {
+        log::warn!(
+            "The process {} was not stopped in time. Killing it.",
+            self.inner.process_name
+        );
+        self.kill_process();
+        self.status.set(Status::WaitProcessKilled);
+        Ok(())
+    }
+
+    async fn do_wait_process_killed(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Error;
+use async_trait::async_trait;
+use bollard::{
+    container::{
+        Config,
+        CreateContainerOptions,
+        LogOutput,
+        LogsOptions,
+        RemoveContainerOptions,
+        RestartContainerOptions,
+        Stats,
+        StatsOptions,
+        StopContainerOptions,
+    },
+    image::{CreateImageOptions, RemoveImageOptions},
+    models::{ContainerInspectResponse, CreateImageInfo, EventMessage, EventMessageTypeEnum},
+    network::{CreateNetworkOptions, InspectNetworkOptions},
+    system::EventsOptions,
+    volume::{CreateVolumeOptions, RemoveVolumeOptions},
+    Docker,
+};
+use futures::{StreamExt, TryStreamExt};
+
+use super::{ContainerRuntime, RuntimeStream};
+
+/// The runtime that uses the local docker daemon.
+#[derive(Clone)]
+pub struct BollardRuntime {
+    docker: Docker,
+}
+
+impl BollardRuntime {
+    pub fn connect() -> Result<Self, Error> {
+        let docker = Docker::connect_with_local_defaults()?;
+        Ok(Self { docker })
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for BollardRuntime {
+    fn events(&self, typ: EventMessageTypeEnum, name: &str) -> RuntimeStream<EventMessage> {
+        let typ = typ.to_string();
+        let mut type_filter = HashMap::new();
+        type_filter.insert("type".to_string(), vec![typ.clone()]);
+        type_filter.insert(typ, vec![name.to_string()]);
+        let opts = EventsOptions {
+            since: None,
+            until: None,
+            filters: type_filter,
+        };
+        self.docker.events(Some(opts)).map_err(Error::from).boxed()
+    }
+
+    async fn image_exists(&self, image: &str) -> bool {
+        self.docker.inspect_image(image).await.is_ok()
+    }
+
+    fn pull_image(&self, image: &str) -> RuntimeStream<CreateImageInfo> {
+        let opts = Some(CreateImageOptions {
+            from_image: image.to_string(),
+            ..Default::default()
+        });
+        self.docker.create_image(opts, None, None).map_err(Error::from).boxed()
+    }
+
+    async fn remove_image(&self, image: &str) -> Result<(), Error> {
+        let opts = Some(RemoveImageOptions {
+            force: true,
+            ..Default::default()
+        });
+        self.docker.remove_image(image, opts, None).await?;
+        Ok(())
+    }
+
+    async fn inspect_container(&self, name: &str) -> Result<ContainerInspectResponse, Error> {
+        let response = self.docker.inspect_container(name, None).await?;
+        Ok(response)
+    }
+
+    async fn create_container(&self, name: &str, config: Config<String>) -> Result<(), Error> {
+        let opts = CreateContainerOptions { name: name.to_string() };
+        self.docker.create_container(Some(opts), config).await?;
+        Ok(())
+    }
+
+    async fn start_container(&self, name: &str) -> Result<(), Error> {
+        self.docker.start_container::<String>(name, None).await?;
+        Ok(())
+    }
+
+    async fn stop_container(&self, name: &str, timeout: Duration) -> Result<(), Error> {
+        let opts = StopContainerOptions {
+            t: timeout.as_secs() as i64,
+        };
+        self.docker.stop_container(name, Some(opts)).await?;
+        Ok(())
+    }
+
+    async fn restart_container(&self, name: &str, timeout: Duration) -> Result<(), Error> {
+        let opts = RestartContainerOptions {
+            t: timeout.as_secs() as isize,
+        };
+        self.docker.restart_container(name, Some(opts)).await?;
+        Ok(())
+    }
+
+    async fn kill_container(&self, name: &str) -> Result<(), Error> {
+        self.docker.kill_container::<String>(name, None).await?;
+        Ok(())
+    }
+
+    async fn remove_container(&self, name: &str) -> Result<(), Error> {
+        let opts = RemoveContainerOptions {
+            force: true,
+            ..Default::default()
+        };
+        self.docker.remove_container(name, Some(opts)).await?;
+        Ok(())
+    }
+
+    fn logs(&self, name: &str) -> RuntimeStream<LogOutput> {
+        let opts = LogsOptions::<String> {
+            follow: true,
+            stdout: true,
+            stderr: true,
+            ..Default::default()
+        };
+        self.docker.logs(name, Some(opts)).map_err(Error::from).boxed()
+    }
+
+    fn stats(&self, name: &str) -> RuntimeStream<Stats> {
+        let opts = StatsOptions {
+            stream: true,
+            one_shot: false,
+        };
+        self.docker.stats(name, Some(opts)).map_err(Error::from).boxed()
+    }
+
+    async fn network_exists(&self, name: &str) -> bool {
+        let opts = InspectNetworkOptions {
+            verbose: false,
+            scope: "local",
+        };
+        self.docker.inspect_network(name, Some(opts)).await.is_ok()
+    }
+
+    async fn create_network(&self, name: &str) -> Result<(), Error> {
+        let options = CreateNetworkOptions {
+            name,
+            check_duplicate: true,
+            driver: "bridge",
+            internal: false,
+            attachable: false,
+            ingress: false,
+            ipam: Default::default(),
+            enable_ipv6: false,
+            options: Default::default(),
+            labels: Default::default(),
+        };
+        self.docker.create_network(options).await?;
+        // TODO: Check warnings...
+        Ok(())
+    }
+
+    async fn remove_network(&self, name: &str) -> Result<(), Error> {
+        self.docker.remove_network(name).await?;
+        Ok(())
+    }
+
+    async fn volume_exists(&self, name: &str) -> bool {
+        self.docker.inspect_volume(name).await.is_ok()
+    }
+
+    async fn create_volume(&self, name: &str) -> Result<(), Error> {
+        let options = CreateVolumeOptions {
+            name,
+            ..Default::default()
+        };
+        self.docker.create_volume(options).await?;
+        // TODO: Check warnings...
+        Ok(())
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<(), Error> {
+        let opts = RemoveVolumeOptions { force: true };
+        self.docker.remove_volume(name, Some(opts)).await?;
+        Ok(())
+    }
+}
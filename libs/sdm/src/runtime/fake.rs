@@ -0,0 +1,361 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use bollard::{
+    container::{Config, LogOutput, Stats},
+    models::{
+        ContainerInspectResponse,
+        ContainerState,
+        CreateImageInfo,
+        EventActor,
+        EventMessage,
+        EventMessageTypeEnum,
+    },
+};
+use futures::{future, stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+
+use super::{ContainerRuntime, RuntimeStream};
+
+#[derive(Default)]
+struct FakeState {
+    images: HashSet<String>,
+    /// Containers with the running flag
+    containers: HashMap<String, bool>,
+    networks: HashSet<String>,
+    volumes: HashSet<String>,
+    /// Images that can't be pulled
+    broken_images: HashSet<String>,
+    /// Events of these types are dropped to emulate an unresponsive daemon
+    muted: Vec<EventMessageTypeEnum>,
+}
+
+/// An in-memory runtime that emits the same events as the docker daemon.
+/// Tests use it to script failures of containers.
+#[derive(Clone)]
+pub struct FakeRuntime {
+    state: Arc<Mutex<FakeState>>,
+    events: broadcast::Sender<EventMessage>,
+    logs: broadcast::Sender<(String, String)>,
+}
+
+impl Default for FakeRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeRuntime {
+    pub fn new() -> Self {
+        let (events, _) = broadcast::channel(64);
+        let (logs, _) = broadcast::channel(64);
+        Self {
+            state: Arc::new(Mutex::new(FakeState::default())),
+            events,
+            logs,
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Emits an event about the object with the `name`.
+    pub fn emit(&self, typ: EventMessageTypeEnum, action: &str, name: &str, attributes: &[(&str, &str)]) {
+        if self.state().muted.contains(&typ) {
+            return;
+        }
+        let mut attrs: HashMap<String, String> = attributes
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        attrs.insert("name".into(), name.into());
+        let event = EventMessage {
+            typ: Some(typ),
+            action: Some(action.into()),
+            actor: Some(EventActor {
+                id: Some(name.into()),
+                attributes: Some(attrs),
+            }),
+            ..Default::default()
+        };
+        // No subscribers is fine
+        self.events.send(event).ok();
+    }
+
+    /// Stops delivering events of the type if `muted` is `true`.
+    pub fn mute(&self, typ: EventMessageTypeEnum, muted: bool) {
+        let mut state = self.state();
+        state.muted.retain(|item| *item != typ);
+        if muted {
+            state.muted.push(typ);
+        }
+    }
+
+    pub fn add_image(&self, image: &str) {
+        self.state().images.insert(image.into());
+    }
+
+    pub fn has_image(&self, image: &str) -> bool {
+        self.state().images.contains(image)
+    }
+
+    /// Pulling of the image will fail.
+    pub fn break_image(&self, image: &str) {
+        self.state().broken_images.insert(image.into());
+    }
+
+    /// Returns `Some(running)` if the container exists.
+    pub fn container(&self, name: &str) -> Option<bool> {
+        self.state().containers.get(name).copied()
+    }
+
+    pub fn has_network(&self, name: &str) -> bool {
+        self.state().networks.contains(name)
+    }
+
+    pub fn has_volume(&self, name: &str) -> bool {
+        self.state().volumes.contains(name)
+    }
+
+    /// The process of the container exits by itself.
+    pub fn exit(&self, name: &str, exit_code: i64) {
+        if let Some(running) = self.state().containers.get_mut(name) {
+            *running = false;
+        }
+        let exit_code = exit_code.to_string();
+        self.emit(EventMessageTypeEnum::CONTAINER, "die", name, &[(
+            "exitCode", &exit_code,
+        )]);
+    }
+
+    /// The container is killed by the OOM killer.
+    pub fn oom(&self, name: &str) {
+        self.emit(EventMessageTypeEnum::CONTAINER, "oom", name, &[]);
+        self.exit(name, 137);
+    }
+
+    /// Adds a record to logs of the container.
+    pub fn log(&self, name: &str, record: &str) {
+        self.logs.send((name.into(), record.into())).ok();
+    }
+
+    fn set_running(&self, name: &str, running: bool) -> Result<(), Error> {
+        let mut state = self.state();
+        let value = state
+            .containers
+            .get_mut(name)
+            .ok_or_else(|| anyhow!("No such container: {}", name))?;
+        *value = running;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ContainerRuntime for FakeRuntime {
+    fn events(&self, typ: EventMessageTypeEnum, name: &str) -> RuntimeStream<EventMessage> {
+        let name = name.to_string();
+        BroadcastStream::new(self.events.subscribe())
+            .filter_map(move |event| {
+                let event = event.ok().filter(|event| {
+                    let same_name = event
+                        .actor
+                        .as_ref()
+                        .and_then(|actor| actor.attributes.as_ref())
+                        .and_then(|attrs| attrs.get("name"))
+                        .map(|value| *value == name)
+                        .unwrap_or_default();
+                    event.typ.as_ref() == Some(&typ) && same_name
+                });
+                future::ready(event.map(Ok))
+            })
+            .boxed()
+    }
+
+    async fn image_exists(&self, image: &str) -> bool {
+        self.state().images.contains(image)
+    }
+
+    fn pull_image(&self, image: &str) -> RuntimeStream<CreateImageInfo> {
+        let this = self.clone();
+        let image = image.to_string();
+        stream::once(async move {
+            if this.state().broken_images.contains(&image) {
+                return Err(anyhow!("Can't pull the image {}", image));
+            }
+            this.add_image(&image);
+            Ok(CreateImageInfo {
+                status: Some(format!("Downloaded newer image for {}", image)),
+                ..Default::default()
+            })
+        })
+        .boxed()
+    }
+
+    async fn remove_image(&self, image: &str) -> Result<(), Error> {
+        if self.state().images.remove(image) {
+            Ok(())
+        } else {
+            Err(anyhow!("No such image: {}", image))
+        }
+    }
+
+    async fn inspect_container(&self, name: &str) -> Result<ContainerInspectResponse, Error> {
+        let running = self
+            .container(name)
+            .ok_or_else(|| anyhow!("No such container: {}", name))?;
+        Ok(ContainerInspectResponse {
+            name: Some(name.into()),
+            state: Some(ContainerState {
+                running: Some(running),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    async fn create_container(&self, name: &str, config: Config<String>) -> Result<(), Error> {
+        {
+            let mut state = self.state();
+            let image = config.image.unwrap_or_default();
+            if !state.images.contains(&image) {
+                return Err(anyhow!("No such image: {}", image));
+            }
+            if state.containers.contains_key(name) {
+                return Err(anyhow!("The container name {} is already in use", name));
+            }
+            state.containers.insert(name.into(), false);
+        }
+        self.emit(EventMessageTypeEnum::CONTAINER, "create", name, &[]);
+        Ok(())
+    }
+
+    async fn start_container(&self, name: &str) -> Result<(), Error> {
+        self.set_running(name, true)?;
+        self.emit(EventMessageTypeEnum::CONTAINER, "start", name, &[]);
+        Ok(())
+    }
+
+    async fn stop_container(&self, name: &str, _timeout: Duration) -> Result<(), Error> {
+        self.set_running(name, false)?;
+        self.emit(EventMessageTypeEnum::CONTAINER, "kill", name, &[("signal", "15")]);
+        self.emit(EventMessageTypeEnum::CONTAINER, "die", name, &[("exitCode", "0")]);
+        Ok(())
+    }
+
+    async fn restart_container(&self, name: &str, timeout: Duration) -> Result<(), Error> {
+        self.stop_container(name, timeout).await?;
+        self.start_container(name).await
+    }
+
+    async fn kill_container(&self, name: &str) -> Result<(), Error> {
+        self.set_running(name, false)?;
+        self.emit(EventMessageTypeEnum::CONTAINER, "kill", name, &[("signal", "9")]);
+        self.emit(EventMessageTypeEnum::CONTAINER, "die", name, &[("exitCode", "137")]);
+        Ok(())
+    }
+
+    async fn remove_container(&self, name: &str) -> Result<(), Error> {
+        let running = self
+            .state()
+            .containers
+            .remove(name)
+            .ok_or_else(|| anyhow!("No such container: {}", name))?;
+        if running {
+            self.emit(EventMessageTypeEnum::CONTAINER, "kill", name, &[("signal", "9")]);
+            self.emit(EventMessageTypeEnum::CONTAINER, "die", name, &[("exitCode", "137")]);
+        }
+        self.emit(EventMessageTypeEnum::CONTAINER, "destroy", name, &[]);
+        Ok(())
+    }
+
+    fn logs(&self, name: &str) -> RuntimeStream<LogOutput> {
+        let name = name.to_string();
+        BroadcastStream::new(self.logs.subscribe())
+            .filter_map(move |record| {
+                let output = record
+                    .ok()
+                    .filter(|(container, _)| *container == name)
+                    .map(|(_, message)| {
+                        Ok(LogOutput::Console {
+                            message: message.into(),
+                        })
+                    });
+                future::ready(output)
+            })
+            .boxed()
+    }
+
+    fn stats(&self, _name: &str) -> RuntimeStream<Stats> {
+        stream::pending().boxed()
+    }
+
+    async fn network_exists(&self, name: &str) -> bool {
+        self.state().networks.contains(name)
+    }
+
+    async fn create_network(&self, name: &str) -> Result<(), Error> {
+        if !self.state().networks.insert(name.into()) {
+            return Err(anyhow!("Network {} already exists", name));
+        }
+        self.emit(EventMessageTypeEnum::NETWORK, "create", name, &[]);
+        Ok(())
+    }
+
+    async fn remove_network(&self, name: &str) -> Result<(), Error> {
+        if !self.state().networks.remove(name) {
+            return Err(anyhow!("No such network: {}", name));
+        }
+        self.emit(EventMessageTypeEnum::NETWORK, "destroy", name, &[]);
+        Ok(())
+    }
+
+    async fn volume_exists(&self, name: &str) -> bool {
+        self.state().volumes.contains(name)
+    }
+
+    async fn create_volume(&self, name: &str) -> Result<(), Error> {
+        if !self.state().volumes.insert(name.into()) {
+            return Err(anyhow!("Volume {} already exists", name));
+        }
+        self.emit(EventMessageTypeEnum::VOLUME, "create", name, &[]);
+        Ok(())
+    }
+
+    async fn remove_volume(&self, name: &str) -> Result<(), Error> {
+        if !self.state().volumes.remove(name) {
+            return Err(anyhow!("No such volume: {}", name));
+        }
+        self.emit(EventMessageTypeEnum::VOLUME, "destroy", name, &[]);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2022. The Tari Project
+//
+// Redistribution and use in source and binary forms, with or without modification, are permitted provided that the
+// following conditions are met:
+//
+// 1. Redistributions of source code must retain the above copyright notice, this list of conditions and the following
+// disclaimer.
+//
+// 2. Redistributions in binary form must reproduce the above copyright notice, this list of conditions and the
+// following disclaimer in the documentation and/or other materials provided with the distribution.
+//
+// 3. Neither the name of the copyright holder nor the names of its contributors may be used to endorse or promote
+// products derived from this software without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND ANY EXPRESS OR IMPLIED WARRANTIES,
+// INCLUDING, BUT NOT LIMITED TO, THE IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL,
+// SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+// SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY,
+// WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
+// USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+//
+
+mod docker;
+pub mod fake;
+
+use std::time::Duration;
+
+use anyhow::Error;
+use async_trait::async_trait;
+use bollard::{
+    container::{Config, LogOutput, Stats},
+    models::{ContainerInspectResponse, CreateImageInfo, EventMessage, EventMessageTypeEnum},
+};
+pub use docker::BollardRuntime;
+pub use fake::FakeRuntime;
+use futures::stream::BoxStream;
+
+pub type RuntimeStream<T> = BoxStream<'static, Result<T, Error>>;
+
+#[async_trait]
+pub trait ContainerRuntime: Send + Sync + 'static {
+    /// Subscribes to events of an object with the `name`.
+    fn events(&self, typ: EventMessageTypeEnum, name: &str) -> RuntimeStream<EventMessage>;
+
+    async fn image_exists(&self, image: &str) -> bool;
+
+    /// Pulls the image. The stream is over when the image is pulled.
+    fn pull_image(&self, image: &str) -> RuntimeStream<CreateImageInfo>;
+
+    async fn remove_image(&self, image: &str) -> Result<(), Error>;
+
+    async fn inspect_container(&self, name: &str) -> Result<ContainerInspectResponse, Error>;
+
+    async fn create_container(&self, name: &str, config: Config<String>) -> Result<(), Error>;
+
+    async fn start_container(&self, name: &str) -> Result<(), Error>;
+
+    /// Sends `SIGTERM` and kills the container after the `timeout`.
+    async fn stop_container(&self, name: &str, timeout: Duration) -> Result<(), Error>;
+
+    async fn restart_container(&self, name: &str, timeout: Duration) -> Result<(), Error>;
+
+    async fn kill_container(&self, name: &str) -> Result<(), Error>;
+
+    /// Removes the container even if it's running.
+    async fn remove_container(&self, name: &str) -> Result<(), Error>;
+
+    fn logs(&self, name: &str) -> RuntimeStream<LogOutput>;
+
+    fn stats(&self, name: &str) -> RuntimeStream<Stats>;
+
+    async fn network_exists(&self, name: &str) -> bool;
+
+    async fn create_network(&self, name: &str) -> Result<(), Error>;
+
+    async fn remove_network(&self, name: &str) -> Result<(), Error>;
+
+    async fn volume_exists(&self, name: &str) -> bool;
+
+    async fn create_volume(&self, name: &str) -> Result<(), Error>;
+
+    async fn remove_volume(&self, name: &str) -> Result<(), Error>;
+}
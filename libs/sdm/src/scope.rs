@@ -21,18 +21,25 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Error};
-use bollard::Docker;
+use futures::future::{BoxFuture, FutureExt};
 use tari_launchpad_protocol::container::{TaskDelta, TaskId, TaskState};
-use tokio::sync::{broadcast, mpsc};
+use tokio::{
+    sync::{broadcast, mpsc},
+    task::JoinHandle,
+    time::{timeout_at, Instant},
+};
 
 use crate::{
     config::ManagedProtocol,
+    graph::DependencyGraph,
     image::{ImageTask, ManagedContainer},
     network::{ManagedNetwork, NetworkTask},
-    task::{ManagedTask, SdmTaskRunner},
+    process::{ManagedProcess, ProcessTask},
+    runtime::{BollardRuntime, ContainerRuntime},
+    task::{ManagedResource, ManagedTask, RunnableContext, RunnableTask, SdmTaskRunner, TaskContext},
     volume::{ManagedVolume, VolumeTask},
 };
 
@@ -51,10 +58,30 @@ pub enum Report<C: ManagedProtocol> {
 
 pub struct SdmScope<C: ManagedProtocol> {
     scope: String,
-    docker: Docker,
+    runtime: Arc<dyn ContainerRuntime>,
     reporter: mpsc::UnboundedReceiver<ReportEnvelope<C>>,
     report_sender: mpsc::UnboundedSender<ReportEnvelope<C>>,
     sender: broadcast::Sender<ControlEvent<C>>,
+    graph: DependencyGraph,
+    /// Registered runners waiting for the `start` call
+    pending: Vec<(TaskId, PendingRunner)>,
+    tasks: Vec<TaskEntry>,
+    order: Vec<TaskId>,
+}
+
+/// The time a runner has to finish after its task has stopped.
+const STOP_MARGIN: Duration = Duration::from_secs(10);
+
+struct PendingRunner {
+    runner: BoxFuture<'static, ()>,
+    stop_timeout: Duration,
+}
+
+struct TaskEntry {
+    task_id: TaskId,
+    deps: Vec<TaskId>,
+    handle: JoinHandle<()>,
+    stop_timeout: Duration,
 }
 
 // TODO: Move to the `task` mod?
@@ -70,6 +97,26 @@ pub enum ControlEvent<C: ManagedProtocol> {
         task_id: TaskId,
     },
     InnerEvent(C::Inner),
+    /// Deactivates the task and stops its runner.
+    Shutdown {
+        task_id: TaskId,
+    },
+    /// Restarts the process of the task or starts a stopped task.
+    Restart {
+        task_id: TaskId,
+    },
+    /// Deactivates the task until it will be restarted.
+    Stop {
+        task_id: TaskId,
+    },
+    /// Pulls the image again and recreates the container.
+    Repull {
+        task_id: TaskId,
+    },
+    /// Removes and creates the container again.
+    Recreate {
+        task_id: TaskId,
+    },
 }
 
 impl<C: ManagedProtocol> Clone for ControlEvent<C> {
@@ -84,70 +131,195 @@ impl<C: ManagedProtocol> Clone for ControlEvent<C> {
                 task_id: task_id.clone(),
             },
             Self::InnerEvent(inner) => Self::InnerEvent(inner.clone()),
+            Self::Shutdown { task_id } => Self::Shutdown {
+                task_id: task_id.clone(),
+            },
+            Self::Restart { task_id } => Self::Restart {
+                task_id: task_id.clone(),
+            },
+            Self::Stop { task_id } => Self::Stop {
+                task_id: task_id.clone(),
+            },
+            Self::Repull { task_id } => Self::Repull {
+                task_id: task_id.clone(),
+            },
+            Self::Recreate { task_id } => Self::Recreate {
+                task_id: task_id.clone(),
+            },
         }
     }
 }
 
+/// Controls a single task of the scope.
+pub struct TaskHandle<C: ManagedProtocol> {
+    task_id: TaskId,
+    sender: broadcast::Sender<ControlEvent<C>>,
+}
+
+impl<C: ManagedProtocol> Clone for TaskHandle<C> {
+    fn clone(&self) -> Self {
+        Self {
+            task_id: self.task_id.clone(),
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<C: ManagedProtocol> TaskHandle<C> {
+    pub fn task_id(&self) -> &TaskId {
+        &self.task_id
+    }
+
+    /// Deactivates the task and stops its runner.
+    pub fn shutdown(&self) -> Result<(), Error> {
+        let task_id = self.task_id.clone();
+        self.send(ControlEvent::Shutdown { task_id })
+    }
+
+    pub fn restart(&self) -> Result<(), Error> {
+        let task_id = self.task_id.clone();
+        self.send(ControlEvent::Restart { task_id })
+    }
+
+    pub fn stop(&self) -> Result<(), Error> {
+        let task_id = self.task_id.clone();
+        self.send(ControlEvent::Stop { task_id })
+    }
+
+    pub fn repull(&self) -> Result<(), Error> {
+        let task_id = self.task_id.clone();
+        self.send(ControlEvent::Repull { task_id })
+    }
+
+    pub fn recreate(&self) -> Result<(), Error> {
+        let task_id = self.task_id.clone();
+        self.send(ControlEvent::Recreate { task_id })
+    }
+
+    fn send(&self, req: ControlEvent<C>) -> Result<(), Error> {
+        self.sender
+            .send(req)
+            .map(drop)
+            .map_err(|req| anyhow!("Can't send a request to {}: {:?}", self.task_id, req))
+    }
+}
+
 impl<C: ManagedProtocol> SdmScope<C> {
     pub fn connect(scope: &str) -> Result<Self, Error> {
-        let docker = Docker::connect_with_local_defaults()?;
+        let runtime = BollardRuntime::connect()?;
+        Ok(Self::with_runtime(scope, Arc::new(runtime)))
+    }
+
+    pub fn with_runtime(scope: &str, runtime: Arc<dyn ContainerRuntime>) -> Self {
         // TODO: Use `rx` later to control entries
         let (req_tx, _req_rx) = broadcast::channel(16);
         let (rep_tx, rep_rx) = mpsc::unbounded_channel();
-        Ok(Self {
+        Self {
             scope: scope.to_string(),
-            docker,
+            runtime,
             reporter: rep_rx,
             report_sender: rep_tx,
             sender: req_tx,
-        })
+            graph: DependencyGraph::default(),
+            pending: Vec::new(),
+            tasks: Vec::new(),
+            order: Vec::new(),
+        }
     }
 
-    pub fn add_image<I>(&mut self, entry: I) -> Result<(), Error>
+    pub fn add_image<I>(&mut self, entry: I) -> Result<TaskHandle<C>, Error>
     where I: ManagedContainer<Protocol = C> + ManagedTask {
-        // TODO: DRY!
-        let entry = Box::new(entry);
-        let inner = ImageTask::new(&self.scope, entry);
-        let runner = SdmTaskRunner::new::<I>(
-            self.sender.clone(),
-            self.report_sender.clone(),
-            inner,
-            self.docker.clone(),
-        );
-        tokio::spawn(runner.entrypoint());
-        Ok(())
+        let inner = ImageTask::new(&self.scope, Box::new(entry));
+        self.add_task::<I, _>(inner)
+    }
+
+    /// Registers a container that runs as a local process instead.
+    pub fn add_process<P>(&mut self, entry: P) -> Result<TaskHandle<C>, Error>
+    where P: ManagedProcess<Protocol = C> + ManagedTask {
+        let inner = ProcessTask::new(&self.scope, Box::new(entry));
+        self.add_task::<P, _>(inner)
     }
 
-    pub fn add_network<N>(&mut self, entry: N) -> Result<(), Error>
+    pub fn add_network<N>(&mut self, entry: N) -> Result<TaskHandle<C>, Error>
     where N: ManagedNetwork<Protocol = C> + ManagedTask {
-        // TODO: DRY!
-        let entry = Box::new(entry);
-        let inner = NetworkTask::new(&self.scope, entry);
-        let runner = SdmTaskRunner::new::<N>(
-            self.sender.clone(),
-            self.report_sender.clone(),
-            inner,
-            self.docker.clone(),
-        );
-        tokio::spawn(runner.entrypoint());
-        Ok(())
+        let inner = NetworkTask::new(&self.scope, Box::new(entry));
+        self.add_task::<N, _>(inner)
     }
 
-    pub fn add_volume<V>(&mut self, entry: V) -> Result<(), Error>
+    pub fn add_volume<V>(&mut self, entry: V) -> Result<TaskHandle<C>, Error>
     where V: ManagedVolume<Protocol = C> + ManagedTask {
-        // TODO: DRY!
-        let entry = Box::new(entry);
-        let inner = VolumeTask::new(&self.scope, entry);
-        let runner = SdmTaskRunner::new::<V>(
+        let inner = VolumeTask::new(&self.scope, Box::new(entry));
+        self.add_task::<V, _>(inner)
+    }
+
+    /// Registers a resource of a custom kind.
+    pub fn add_resource<M>(&mut self, resource: M) -> Result<TaskHandle<C>, Error>
+    where
+        M: ManagedResource,
+        M::Task: RunnableTask<Protocol = C>,
+        TaskContext<M::Task>: RunnableContext<M::Task>,
+    {
+        let inner = resource.into_task(&self.scope);
+        self.add_task::<M, _>(inner)
+    }
+
+    /// Registers a runner of the task. The id, dependencies and
+    /// the restart policy are taken from `M`.
+    pub fn add_task<M, R>(&mut self, inner: R) -> Result<TaskHandle<C>, Error>
+    where
+        M: ManagedTask,
+        R: RunnableTask<Protocol = C>,
+        TaskContext<R>: RunnableContext<R>,
+    {
+        let stop_timeout = inner.stop_timeout();
+        let runner = SdmTaskRunner::new::<M>(
             self.sender.clone(),
             self.report_sender.clone(),
             inner,
-            self.docker.clone(),
+            self.runtime.clone(),
         );
-        tokio::spawn(runner.entrypoint());
+        let runner = PendingRunner {
+            runner: runner.entrypoint().boxed(),
+            stop_timeout,
+        };
+        self.register::<M>(runner)?;
+        Ok(TaskHandle {
+            task_id: M::id(),
+            sender: self.sender.clone(),
+        })
+    }
+
+    fn register<M: ManagedTask>(&mut self, runner: PendingRunner) -> Result<(), Error> {
+        self.graph.add(M::id(), M::deps())?;
+        self.pending.push((M::id(), runner));
         Ok(())
     }
 
+    /// Validates dependencies of the registered tasks and starts them.
+    /// Nothing is started if the graph has unknown ids or cycles.
+    pub fn start(&mut self) -> Result<(), Error> {
+        let order = self.graph.resolve()?;
+        let mut pending: HashMap<_, _> = self.pending.drain(..).collect();
+        for task_id in &order {
+            if let Some(pending) = pending.remove(task_id) {
+                let entry = TaskEntry {
+                    task_id: task_id.clone(),
+                    deps: self.graph.deps(task_id).unwrap_or_default().to_vec(),
+                    handle: tokio::spawn(pending.runner),
+                    stop_timeout: pending.stop_timeout,
+                };
+                self.tasks.push(entry);
+            }
+        }
+        self.order = order;
+        Ok(())
+    }
+
+    /// Tasks in the topological order: every task follows its dependencies.
+    pub fn order(&self) -> &[TaskId] {
+        &self.order
+    }
+
     pub fn set_config(&mut self, config: Option<C::Config>) -> Result<(), Error> {
         let config = config.map(Arc::new);
         let req = ControlEvent::SetConfig(config);
@@ -165,5 +337,57 @@ impl<C: ManagedProtocol> SdmScope<C> {
         self.reporter.recv().await
     }
 
-    pub fn stop(&self) {}
+    /// Returns a report if it's available without waiting.
+    pub fn try_recv(&mut self) -> Option<ReportEnvelope<C>> {
+        self.reporter.try_recv().ok()
+    }
+
+    /// Deactivates all the tasks in the reverse order of dependencies:
+    /// a task is stopped only when all its dependants are down.
+    /// Resolves when all the runners have finished. A runner that
+    /// doesn't finish in the stop timeout of its task is aborted.
+    pub async fn stop(&mut self) -> Result<(), Error> {
+        let mut tasks: HashMap<TaskId, TaskEntry> = self
+            .tasks
+            .drain(..)
+            .map(|entry| (entry.task_id.clone(), entry))
+            .collect();
+        while !tasks.is_empty() {
+            let level: Vec<TaskId> = tasks
+                .keys()
+                .filter(|task_id| !tasks.values().any(|entry| entry.deps.contains(task_id)))
+                .cloned()
+                .collect();
+            if level.is_empty() {
+                return Err(anyhow!("Can't stop tasks with cyclic dependencies"));
+            }
+            let mut entries = Vec::new();
+            for task_id in level {
+                if let Some(entry) = tasks.remove(&task_id) {
+                    log::info!("Stopping the task {}", task_id);
+                    let req = ControlEvent::Shutdown { task_id };
+                    if let Err(err) = self.send(req) {
+                        log::warn!("Runners are not available: {}", err);
+                    }
+                    entries.push(entry);
+                }
+            }
+            let stop_timeout = entries.iter().map(|entry| entry.stop_timeout).max().unwrap_or_default();
+            let deadline = Instant::now() + stop_timeout + STOP_MARGIN;
+            for mut entry in entries {
+                match timeout_at(deadline, &mut entry.handle).await {
+                    Ok(Ok(())) => {},
+                    Ok(Err(err)) => {
+                        log::error!("Task runner failed: {}", err);
+                    },
+                    Err(_) => {
+                        // A runner could retry a failing update forever
+                        log::error!("The task {} was not stopped in time. Aborting it.", entry.task_id);
+                        entry.handle.abort();
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
 }
@@ -22,7 +22,7 @@
 //
 
 use derive_more::Deref;
-use tokio::time::Instant;
+use tokio::time::{Duration, Instant};
 
 use crate::task::TaskStatusChecker;
 
@@ -56,6 +56,10 @@ impl<S> SdmStatus<S> {
         &self.status
     }
 
+    pub fn fallback_deadline(&self) -> Option<Instant> {
+        self.fallback.as_ref().map(|fallback| fallback.when)
+    }
+
     pub fn has_work(&self) -> bool {
         self.has_work
     }
@@ -69,7 +73,7 @@ impl<S: TaskStatusChecker> SdmStatus<S> {
     pub fn check_fallback(&mut self) {
         if let Some(fallback) = self.fallback.as_ref() {
             let now = Instant::now();
-            if fallback.when < now {
+            if fallback.when <= now {
                 let fallback = self.fallback.take().unwrap();
                 self.set(fallback.next_status);
             }
@@ -88,7 +92,14 @@ impl<S: TaskStatusChecker> SdmStatus<S> {
         func(&mut self.status);
     }
 
-    // pub fn set_fallback(&mut self, fallback: Fallback<S>) {
-    // self.fallback = Some(fallback);
-    // }
+    /// Sets the status that will be replaced with `next_status`
+    /// if it won't be changed within the `timeout`.
+    pub fn set_with_fallback(&mut self, status: S, timeout: Duration, next_status: S) {
+        self.set(status);
+        let fallback = Fallback {
+            when: Instant::now() + timeout,
+            next_status,
+        };
+        self.fallback = Some(fallback);
+    }
 }
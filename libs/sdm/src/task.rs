@@ -21,23 +21,36 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::{collections::HashMap, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 use anyhow::Error;
 use async_trait::async_trait;
-use bollard::Docker;
+use chrono::Local;
 use derive_more::{Deref, DerefMut};
-use futures::StreamExt;
-use tari_launchpad_protocol::container::{StatsData, TaskDelta, TaskId, TaskState, TaskStatus as TaskStatusValue};
+use futures::{future, StreamExt};
+use tari_launchpad_protocol::container::{
+    ExitInfo,
+    StatsData,
+    TaskDelta,
+    TaskId,
+    TaskState,
+    TaskStatus as TaskStatusValue,
+};
 use tokio::{
     select,
     sync::{broadcast, mpsc},
-    time::{sleep, Duration, Instant},
+    time::{sleep_until, Duration, Instant},
 };
 use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 
 use crate::{
     config::ManagedProtocol,
+    policy::{RestartPolicy, Restarts},
+    runtime::ContainerRuntime,
     scope::{ControlEvent, Report, ReportEnvelope},
     status::SdmStatus,
 };
@@ -48,12 +61,29 @@ pub trait ManagedTask {
     fn deps() -> Vec<TaskId> {
         Vec::default()
     }
+
+    /// How to restart the task if it fails.
+    fn restart_policy() -> RestartPolicy {
+        RestartPolicy::default()
+    }
+}
+
+/// A resource of a custom kind that can be added to a scope.
+pub trait ManagedResource: ManagedTask {
+    type Task: RunnableTask;
+
+    fn into_task(self, scope: &str) -> Self::Task;
 }
 
 pub trait TaskStatusChecker: fmt::Debug + Default + Send {
     fn is_ready(&self) -> bool {
         false
     }
+
+    /// The task doesn't hold any running resources and can be dropped.
+    fn is_down(&self) -> bool {
+        false
+    }
 }
 
 #[async_trait]
@@ -65,6 +95,11 @@ pub trait RunnableTask: Sized + Send + 'static {
     fn name(&self) -> &str;
 
     fn is_permanent(&self) -> bool;
+
+    /// How long the task could take to stop gracefully.
+    fn stop_timeout(&self) -> Duration {
+        Duration::ZERO
+    }
 }
 
 #[async_trait]
@@ -73,10 +108,29 @@ pub trait RunnableContext<T: RunnableTask> {
     async fn initialize(&mut self);
     fn reconfigure(&mut self, config: Option<&<T::Protocol as ManagedProtocol>::Config>) -> bool;
     fn process_inner_event(&mut self, event: <T::Protocol as ManagedProtocol>::Inner);
+    /// The task is not disabled by the config.
+    fn is_available(&self) -> bool {
+        true
+    }
+    /// Dependencies the task doesn't wait for with the current config.
+    fn skipped_dependencies(&self) -> Vec<TaskId> {
+        Vec::new()
+    }
     fn process_event(&mut self, event: T::Event) -> Result<(), Error>;
+    /// Handles a command from a user. `Stop` is handled by the runner.
+    fn process_command(&mut self, _command: TaskCommand) {}
     async fn update(&mut self) -> Result<(), Error>;
 }
 
+/// Commands from a user to control a single task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskCommand {
+    Restart,
+    Stop,
+    Repull,
+    Recreate,
+}
+
 pub struct TaskSender<E, P: ManagedProtocol> {
     task_id: TaskId,
     event_tx: mpsc::UnboundedSender<E>,
@@ -142,6 +196,22 @@ impl<E, P: ManagedProtocol> TaskSender<E, P> {
         let report = Report::Delta(delta);
         self.send_report(report)
     }
+
+    pub fn send_exit(&self, info: ExitInfo) -> Result<(), Error> {
+        let delta = TaskDelta::Exited(info);
+        let report = Report::Delta(delta);
+        self.send_report(report)
+    }
+}
+
+/// Why the task is kept down regardless of the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lifecycle {
+    Running,
+    /// Stopped by a user until the next restart
+    Stopped,
+    /// The scope is stopping
+    ShuttingDown,
 }
 
 #[derive(Deref, DerefMut)]
@@ -151,9 +221,13 @@ pub struct TaskContext<T: RunnableTask> {
     resources_map: HashMap<TaskId, String>,
     /// Depends on the config
     should_start: bool,
+    lifecycle: Lifecycle,
+    /// Inactive status is reported as unavailable if not set
+    available: bool,
     pub status: SdmStatus<T::Status>,
+    pub restarts: Restarts,
     sender: TaskSender<T::Event, T::Protocol>,
-    pub driver: Docker,
+    pub driver: Arc<dyn ContainerRuntime>,
     #[deref]
     #[deref_mut]
     pub inner: T,
@@ -161,7 +235,7 @@ pub struct TaskContext<T: RunnableTask> {
 
 impl<T: RunnableTask> TaskContext<T> {
     pub fn should_be_active(&self) -> bool {
-        self.should_start && self.dependencies_ready
+        self.should_start && self.dependencies_ready && self.lifecycle == Lifecycle::Running
     }
 
     pub fn resource(&self, id: &TaskId) -> Option<&str> {
@@ -173,10 +247,42 @@ impl<T: RunnableTask> TaskContext<T> {
     }
 
     pub fn update_task_status(&self, status: TaskStatusValue) -> Result<(), Error> {
+        let status = match status {
+            TaskStatusValue::Inactive if !self.available => TaskStatusValue::Unavailable,
+            status => status,
+        };
         let delta = TaskDelta::UpdateStatus(status);
         let report = Report::Delta(delta);
         self.sender().send_report(report)
     }
+
+    /// Registers a failure and reports it with the time of the next attempt.
+    pub fn report_failure(&mut self, reason: String) -> Result<(), Error> {
+        let delay = self.restarts.register_failure();
+        self.report_retry(reason, delay)
+    }
+
+    /// Registers an exit of the task and reports if it will be restarted.
+    pub fn report_exit(&mut self, info: ExitInfo) -> Result<(), Error> {
+        let delay = self.restarts.register_exit(info.is_success());
+        if info.is_success() && delay.is_none() {
+            self.update_task_status(TaskStatusValue::Inactive)
+        } else {
+            self.report_retry(info.to_string(), delay)
+        }
+    }
+
+    fn report_retry(&mut self, reason: String, delay: Option<Duration>) -> Result<(), Error> {
+        let next_retry = delay
+            .and_then(|delay| chrono::Duration::from_std(delay).ok())
+            .map(|delay| Local::now().naive_local() + delay);
+        let status = TaskStatusValue::Failed {
+            reason,
+            attempts: self.restarts.attempts(),
+            next_retry,
+        };
+        self.update_task_status(status)
+    }
 }
 
 pub struct SdmTaskRunner<R: RunnableTask> {
@@ -185,9 +291,10 @@ pub struct SdmTaskRunner<R: RunnableTask> {
     requests_receiver: Option<broadcast::Receiver<ControlEvent<R::Protocol>>>,
     requests_sender: broadcast::Sender<ControlEvent<R::Protocol>>,
     context: TaskContext<R>,
-    next_update: Instant,
     /// Waits when these dependencies started.
     dependencies: HashMap<TaskId, bool>,
+    /// Dependencies that are not required by the config.
+    skipped: HashSet<TaskId>,
     ready_to_use: bool,
 }
 
@@ -198,7 +305,7 @@ where TaskContext<R>: RunnableContext<R>
         req_tx: broadcast::Sender<ControlEvent<R::Protocol>>,
         rep_tx: mpsc::UnboundedSender<ReportEnvelope<R::Protocol>>,
         inner: R,
-        docker: Docker,
+        driver: Arc<dyn ContainerRuntime>,
     ) -> Self {
         let task_id = M::id();
         let (event_tx, event_rx) = mpsc::unbounded_channel();
@@ -212,9 +319,12 @@ where TaskContext<R>: RunnableContext<R>
             dependencies_ready: false,
             resources_map: HashMap::new(),
             should_start: false,
+            lifecycle: Lifecycle::Running,
+            available: true,
             status: SdmStatus::new(inner.name().to_string()),
+            restarts: Restarts::new(M::restart_policy()),
             sender,
-            driver: docker,
+            driver,
             inner,
         };
         // It subscribed here to avoid the gap if
@@ -228,8 +338,8 @@ where TaskContext<R>: RunnableContext<R>
             requests_receiver: Some(req_rx),
             requests_sender: req_tx,
             context,
-            next_update: Instant::now(),
             dependencies,
+            skipped: HashSet::new(),
             ready_to_use: false,
         }
     }
@@ -243,15 +353,17 @@ where TaskContext<R>: RunnableContext<R>
     pub async fn routine(&mut self) -> Result<(), Error> {
         self.check_dependencies();
         self.initialize().await?;
-        let interval = Duration::from_millis(1_000);
         let events_receiver = self.events_receiver.take().unwrap();
         let mut events = UnboundedReceiverStream::new(events_receiver);
         let requests_receiver = self.requests_receiver.take().unwrap();
         let mut requests = BroadcastStream::new(requests_receiver);
+        self.update().await;
+        self.notify_dependants();
         loop {
+            let deadline = self.next_deadline();
             select! {
-                _ = sleep(interval) => {
-                    // log::trace!("Checking the scope by interval");
+                _ = wait_until(deadline) => {
+                    log::trace!("Deadline of the task {} reached", self.task_id);
                 }
                 event = events.next() => {
                     if let Some(event) = event {
@@ -272,15 +384,29 @@ where TaskContext<R>: RunnableContext<R>
             }
             self.update().await;
             self.notify_dependants();
+            if self.context.lifecycle == Lifecycle::ShuttingDown && self.context.status.is_down() {
+                log::info!("Task {} is down", self.task_id);
+                break;
+            }
         }
         Ok(())
     }
 
+    /// The nearest moment when the task has to be updated
+    /// even if no events received.
+    fn next_deadline(&self) -> Option<Instant> {
+        let now = Instant::now();
+        let retry = self.context.restarts.next_retry().filter(|when| *when > now);
+        let fallback = self.context.status.fallback_deadline();
+        retry.into_iter().chain(fallback).min()
+    }
+
     fn notify_dependants(&mut self) {
         if self.context.status.is_ready() {
             // Notifies dependants about the entity is ready to use
             if !self.ready_to_use {
                 self.ready_to_use = true;
+                self.context.restarts.reset();
                 let task_id = self.task_id.clone();
                 let name = self.context.name().to_owned();
                 let event = ControlEvent::ResourceReady { task_id, name };
@@ -328,12 +454,38 @@ where TaskContext<R>: RunnableContext<R>
             ControlEvent::InnerEvent(inner) => {
                 self.process_inner_event(inner);
             },
+            ControlEvent::Shutdown { task_id } => {
+                if task_id == self.task_id {
+                    self.context.lifecycle = Lifecycle::ShuttingDown;
+                    // Don't wait for the next attempt to deactivate the task
+                    self.context.restarts.reset();
+                }
+            },
+            ControlEvent::Restart { task_id } if task_id == self.task_id => {
+                self.process_command(TaskCommand::Restart);
+            },
+            ControlEvent::Stop { task_id } if task_id == self.task_id => {
+                self.process_command(TaskCommand::Stop);
+            },
+            ControlEvent::Repull { task_id } if task_id == self.task_id => {
+                self.process_command(TaskCommand::Repull);
+            },
+            ControlEvent::Recreate { task_id } if task_id == self.task_id => {
+                self.process_command(TaskCommand::Recreate);
+            },
+            ControlEvent::Restart { .. } |
+            ControlEvent::Stop { .. } |
+            ControlEvent::Repull { .. } |
+            ControlEvent::Recreate { .. } => {},
         }
     }
 
     fn check_dependencies(&mut self) {
         // If the set is empty `all` returs `true`.
-        self.context.dependencies_ready = self.dependencies.values().all(|ready| *ready);
+        self.context.dependencies_ready = self
+            .dependencies
+            .iter()
+            .all(|(task_id, ready)| *ready || self.skipped.contains(task_id));
     }
 
     pub async fn initialize(&mut self) -> Result<(), Error> {
@@ -348,6 +500,35 @@ where TaskContext<R>: RunnableContext<R>
     pub fn reconfigure(&mut self, config: Option<&<R::Protocol as ManagedProtocol>::Config>) {
         let active = self.context.reconfigure(config);
         self.context.should_start = active;
+        self.skipped = self.context.skipped_dependencies().into_iter().collect();
+        self.check_dependencies();
+        let available = self.context.is_available();
+        if available != self.context.available {
+            self.context.available = available;
+            // Updates the reported status if the task has nothing to stop
+            if self.context.status.is_down() && !self.context.status.is_ready() {
+                if let Err(err) = self.context.update_task_status(TaskStatusValue::Inactive) {
+                    log::error!("Can't report the availability: {}", err);
+                }
+            }
+        }
+        // The config could fix the reason of the failure
+        self.context.restarts.resume();
+    }
+
+    pub fn process_command(&mut self, command: TaskCommand) {
+        log::info!("Command {:?} for the task {}", command, self.task_id);
+        // A user asked explicitly, so the task gets a fresh start
+        self.context.restarts.reset();
+        // The shutdown can't be cancelled by a command
+        if self.context.lifecycle != Lifecycle::ShuttingDown {
+            self.context.lifecycle = if command == TaskCommand::Stop {
+                Lifecycle::Stopped
+            } else {
+                Lifecycle::Running
+            };
+        }
+        self.context.process_command(command);
     }
 
     pub fn process_inner_event(&mut self, event: <R::Protocol as ManagedProtocol>::Inner) {
@@ -368,16 +549,16 @@ where TaskContext<R>: RunnableContext<R>
             self.context.status.get()
         );
         loop {
-            let now = Instant::now();
-            if self.next_update > now {
-                continue;
+            if self.context.restarts.is_waiting() {
+                break;
             }
             self.context.status.check_fallback();
             self.context.status.reset_has_work_flag();
             if let Err(err) = self.context.update().await {
                 log::error!("Update error: {}", err);
-                self.next_update = now + Duration::from_secs(5);
-                // TODO: Set a fallback? and reset it when succeed?
+                if let Err(err) = self.context.report_failure(err.to_string()) {
+                    log::error!("Can't report the failure: {}", err);
+                }
                 break;
             }
             if !self.context.status.has_work() {
@@ -388,3 +569,11 @@ where TaskContext<R>: RunnableContext<R>
 }
 
 pub trait TaskEvent: fmt::Debug + Send {}
+
+async fn wait_until(deadline: Option<Instant>) {
+    if let Some(deadline) = deadline {
+        sleep_until(deadline).await;
+    } else {
+        future::pending::<()>().await;
+    }
+}
@@ -37,5 +37,10 @@ pub trait ManagedVolume: fmt::Debug + Send + 'static {
         config.is_some()
     }
 
+    /// The volume is disabled by the config. The existing volume is removed.
+    fn is_available(&self) -> bool {
+        true
+    }
+
     fn volume_name(&self) -> &str;
 }
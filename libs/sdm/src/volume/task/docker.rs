@@ -21,15 +21,8 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 //
 
-use std::collections::HashMap;
-
 use anyhow::Error;
-use bollard::{
-    models::{EventMessage, EventMessageTypeEnum},
-    system::EventsOptions,
-    volume::CreateVolumeOptions,
-};
-use futures::TryStreamExt;
+use bollard::models::{EventMessage, EventMessageTypeEnum};
 
 use super::{Event, VolumeTask};
 use crate::{
@@ -40,15 +33,9 @@ use crate::{
 
 impl<C: ManagedProtocol> TaskContext<VolumeTask<C>> {
     pub fn subscribe_to_events(&mut self) {
-        let mut type_filter = HashMap::new();
-        type_filter.insert("type".to_string(), vec!["volume".to_string()]);
-        type_filter.insert("volume".to_string(), vec![self.inner.volume_name.clone()]);
-        let opts = EventsOptions {
-            since: None,
-            until: None,
-            filters: type_filter,
-        };
-        let stream = self.driver.events(Some(opts)).map_err(Error::from);
+        let stream = self
+            .driver
+            .events(EventMessageTypeEnum::VOLUME, &self.inner.volume_name);
         let sender = self.sender().get_direct().clone();
         let conv = EventConv {
             // TODO: Name is not necessary here
@@ -59,25 +46,16 @@ impl<C: ManagedProtocol> TaskContext<VolumeTask<C>> {
     }
 
     pub async fn volume_exists(&mut self) -> bool {
-        // TODO: Move to context directly
-        self.driver.inspect_volume(&self.inner.volume_name).await.is_ok()
+        self.driver.volume_exists(&self.inner.volume_name).await
     }
 
     pub async fn try_create_volume(&mut self) -> Result<(), Error> {
-        let options = CreateVolumeOptions {
-            name: self.inner.volume_name.as_ref() as &str,
-            ..Default::default()
-        };
-        self.driver.create_volume(options).await?;
-        // TODO: Check warnings...
-        Ok(())
+        self.driver.create_volume(&self.inner.volume_name).await
     }
 
-    // pub async fn try_remove_volume(&mut self) -> Result<(), Error> {
-    // let opts = RemoveVolumeOptions { force: true };
-    // self.driver.remove_volume(&self.inner.volume_name, Some(opts)).await?;
-    // Ok(())
-    // }
+    pub async fn try_remove_volume(&mut self) -> Result<(), Error> {
+        self.driver.remove_volume(&self.inner.volume_name).await
+    }
 }
 
 struct EventConv {
@@ -44,6 +44,11 @@ impl<C: ManagedProtocol> TaskContext<VolumeTask<C>> {
     }
 
     fn on_destroyed(&mut self) -> Result<(), Error> {
+        if let Status::Active = self.status.get() {
+            // Removed outside, will be created again if required
+            self.status.set(Status::Idle);
+            self.update_task_status(TaskStatus::Inactive)?;
+        }
         Ok(())
     }
 }
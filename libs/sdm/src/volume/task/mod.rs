@@ -83,6 +83,10 @@ impl<C: ManagedProtocol> RunnableContext<VolumeTask<C>> for TaskContext<VolumeTa
         log::warn!("Inner event is ignored by a volume task");
     }
 
+    fn is_available(&self) -> bool {
+        self.inner.volume.is_available()
+    }
+
     fn process_event(&mut self, event: Event) -> Result<(), Error> {
         self.process_event_impl(event)
     }
@@ -95,15 +99,25 @@ impl<C: ManagedProtocol> RunnableContext<VolumeTask<C>> for TaskContext<VolumeTa
 #[derive(Debug)]
 pub enum Status {
     InitialState,
+    /// Waits for the config
+    Idle,
     Checking,
     WaitCreating,
     Active,
+    RemoveVolume,
+    /// The volume is in use and will be removed later
+    WaitRemoving,
 }
 
 impl TaskStatusChecker for Status {
     fn is_ready(&self) -> bool {
         matches!(self, Self::Active)
     }
+
+    fn is_down(&self) -> bool {
+        // Volumes are kept on shutdown
+        true
+    }
 }
 
 impl Default for Status {
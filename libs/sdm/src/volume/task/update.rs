@@ -22,24 +22,45 @@
 //
 
 use anyhow::Error;
-use tari_launchpad_protocol::container::TaskStatus;
+use tari_launchpad_protocol::container::{TaskProgress, TaskStatus};
+use tokio::time::Duration;
 
 use super::{Status, VolumeTask};
-use crate::{config::ManagedProtocol, task::TaskContext};
+use crate::{
+    config::ManagedProtocol,
+    task::{RunnableContext, TaskContext},
+};
+
+/// How often to try to remove a volume that is in use.
+const REMOVE_INTERVAL: Duration = Duration::from_secs(2);
 
 impl<C: ManagedProtocol> TaskContext<VolumeTask<C>> {
     pub async fn process_update_impl(&mut self) -> Result<(), Error> {
         match self.status.get() {
             Status::InitialState => self.do_initial_state().await,
+            Status::Idle => self.do_idle().await,
             Status::Checking => self.do_checking().await,
             Status::WaitCreating => self.do_wait_creating().await,
             Status::Active => self.do_active().await,
+            Status::RemoveVolume => self.do_remove_volume().await,
+            Status::WaitRemoving => self.do_wait_removing().await,
         }
     }
 
     async fn do_initial_state(&mut self) -> Result<(), Error> {
         self.update_task_status(TaskStatus::Inactive)?;
-        self.status.set(Status::Checking);
+        self.status.set(Status::Idle);
+        Ok(())
+    }
+
+    async fn do_idle(&mut self) -> Result<(), Error> {
+        if self.should_be_active() {
+            self.status.set(Status::Checking);
+        } else if !self.is_available() && self.volume_exists().await {
+            self.status.set(Status::RemoveVolume);
+        } else {
+            // Waits for the config or the dependencies
+        }
         Ok(())
     }
 
@@ -60,9 +81,31 @@ impl<C: ManagedProtocol> TaskContext<VolumeTask<C>> {
     }
 
     async fn do_active(&mut self) -> Result<(), Error> {
-        if !self.should_be_active() {
-            // self.status.set(Status::Checking);
+        // The volume is kept on shutdown
+        if !self.is_available() {
+            self.status.set(Status::RemoveVolume);
+            let progress = TaskProgress::new("Removing...");
+            self.update_task_status(TaskStatus::Progress(progress))?;
+        }
+        Ok(())
+    }
+
+    async fn do_remove_volume(&mut self) -> Result<(), Error> {
+        if self.is_available() {
+            self.status.set(Status::Idle);
+        } else if let Err(err) = self.try_remove_volume().await {
+            // Containers that use the volume could be not removed yet
+            log::debug!("Can't remove the volume {}: {}", self.inner.volume_name, err);
+            self.status
+                .set_with_fallback(Status::WaitRemoving, REMOVE_INTERVAL, Status::RemoveVolume);
+        } else {
+            self.status.set(Status::Idle);
+            self.update_task_status(TaskStatus::Inactive)?;
         }
         Ok(())
     }
+
+    async fn do_wait_removing(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
 }
@@ -0,0 +1,78 @@
+// Every test binary uses a part of the fixture
+#![allow(dead_code)]
+
+use std::{collections::HashMap, fmt, marker::PhantomData, sync::Arc};
+
+use anyhow::{anyhow, Error};
+use tari_launchpad_protocol::container::{TaskState, TaskStatus};
+use tari_sdm::{config::ManagedProtocol, ids::TaskId, runtime::FakeRuntime, Report, SdmScope};
+use tokio::time::{timeout, Duration};
+
+/// A protocol of test tasks that are configured by `C`.
+#[derive(Debug)]
+pub struct TestProtocol<C = bool>(PhantomData<C>);
+
+impl<C> ManagedProtocol for TestProtocol<C>
+where C: fmt::Debug + Sync + Send + 'static
+{
+    type Config = C;
+    type Inner = ();
+    type Outer = ();
+}
+
+pub struct TestScope<C = bool> {
+    pub scope: SdmScope<TestProtocol<C>>,
+    pub runtime: FakeRuntime,
+    states: HashMap<TaskId, TaskState>,
+}
+
+impl<C> TestScope<C>
+where C: fmt::Debug + Sync + Send + 'static
+{
+    /// Starts the scope with the tasks added by `add` and sets the config.
+    pub fn start<F>(runtime: FakeRuntime, config: C, add: F) -> Result<Self, Error>
+    where F: FnOnce(&mut SdmScope<TestProtocol<C>>) -> Result<(), Error> {
+        let mut scope = SdmScope::with_runtime("test", Arc::new(runtime.clone()));
+        add(&mut scope)?;
+        scope.start()?;
+        scope.set_config(Some(config))?;
+        Ok(Self {
+            scope,
+            runtime,
+            states: HashMap::new(),
+        })
+    }
+
+    /// Processes reports until the state of the task matches.
+    pub async fn wait_for<F>(&mut self, task_id: &TaskId, check: F) -> Result<TaskState, Error>
+    where F: Fn(&TaskState) -> bool {
+        let waiting = async {
+            loop {
+                if let Some(state) = self.states.get(task_id) {
+                    if check(state) {
+                        return Ok(state.clone());
+                    }
+                }
+                let report = self.scope.recv().await.ok_or_else(|| anyhow!("Scope closed"))?;
+                match report.details {
+                    Report::State(state) => {
+                        self.states.insert(report.task_id, state);
+                    },
+                    Report::Delta(delta) => {
+                        if let Some(state) = self.states.get_mut(&report.task_id) {
+                            state.apply(delta);
+                        }
+                    },
+                    Report::Extras(_) => {},
+                }
+            }
+        };
+        timeout(Duration::from_secs(300), waiting).await?
+    }
+
+    /// Processes reports until the status of the task matches.
+    pub async fn wait_for_status<F>(&mut self, task_id: &TaskId, check: F) -> Result<TaskState, Error>
+    where F: Fn(&TaskStatus) -> bool {
+        self.wait_for(task_id, |state| check(&state.status)).await
+    }
+}
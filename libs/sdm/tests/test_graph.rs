@@ -0,0 +1,46 @@
+use tari_sdm::{
+    graph::{DependencyGraph, GraphError},
+    ids::TaskId,
+};
+
+fn ids(ids: &[&str]) -> Vec<TaskId> {
+    ids.iter().copied().map(TaskId::from).collect()
+}
+
+#[test]
+fn test_topological_order() {
+    let mut graph = DependencyGraph::default();
+    graph.add("Wallet".into(), ids(&["Base Node", "LocalNet"])).unwrap();
+    graph.add("Base Node".into(), ids(&["Tor", "LocalNet"])).unwrap();
+    graph.add("Tor".into(), ids(&["LocalNet"])).unwrap();
+    graph.add("LocalNet".into(), vec![]).unwrap();
+    let order = graph.resolve().unwrap();
+    assert_eq!(order, ids(&["LocalNet", "Tor", "Base Node", "Wallet"]));
+}
+
+#[test]
+fn test_unknown_dependency() {
+    let mut graph = DependencyGraph::default();
+    graph.add("Xmrig".into(), ids(&["MM Proxy"])).unwrap();
+    graph.add("MM proxy".into(), vec![]).unwrap();
+    let err = graph.resolve().unwrap_err();
+    assert!(matches!(err, GraphError::UnknownDependency { .. }));
+    assert_eq!(err.to_string(), "Task Xmrig depends on the unknown task MM Proxy");
+}
+
+#[test]
+fn test_cycle() {
+    let mut graph = DependencyGraph::default();
+    graph.add("A".into(), ids(&["B"])).unwrap();
+    graph.add("B".into(), ids(&["C"])).unwrap();
+    graph.add("C".into(), ids(&["A"])).unwrap();
+    let err = graph.resolve().unwrap_err();
+    assert_eq!(err.to_string(), "Tasks have a cyclic dependency: A -> B -> C -> A");
+}
+
+#[test]
+fn test_duplicate() {
+    let mut graph = DependencyGraph::default();
+    graph.add("A".into(), vec![]).unwrap();
+    assert!(matches!(graph.add("A".into(), vec![]), Err(GraphError::Duplicate(_))));
+}
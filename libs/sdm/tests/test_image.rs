@@ -0,0 +1,114 @@
+mod common;
+
+use anyhow::Error;
+use common::{TestProtocol, TestScope};
+use tari_launchpad_protocol::container::TaskStatus;
+use tari_sdm::{
+    ids::{ManagedTask, TaskId},
+    image::{image_reference, ManagedContainer},
+    runtime::FakeRuntime,
+};
+
+const DIGEST: &str = "sha256:8b0f5a1a9e35b7b5d8c5f6d3f3b2a8b0a7c9e5d1f2c4b6a8e0d2f4a6c8e0b2d4";
+
+#[derive(Debug)]
+struct TestConfig {
+    tag: String,
+    enabled: bool,
+}
+
+#[derive(Debug, Default)]
+struct Tagged {
+    tag: String,
+    enabled: bool,
+}
+
+impl ManagedTask for Tagged {
+    fn id() -> TaskId {
+        "Tagged".into()
+    }
+}
+
+impl ManagedContainer for Tagged {
+    type Protocol = TestProtocol<TestConfig>;
+
+    fn reconfigure(&mut self, config: Option<&TestConfig>) -> Option<bool> {
+        let config = config?;
+        self.tag = config.tag.clone();
+        self.enabled = config.enabled;
+        Some(config.enabled)
+    }
+
+    fn is_available(&self) -> bool {
+        self.enabled
+    }
+
+    fn registry(&self) -> &str {
+        "test"
+    }
+
+    fn image_name(&self) -> &str {
+        "tagged"
+    }
+
+    fn tag(&self) -> &str {
+        &self.tag
+    }
+}
+
+fn config(tag: &str, enabled: bool) -> TestConfig {
+    TestConfig {
+        tag: tag.into(),
+        enabled,
+    }
+}
+
+fn start(tag: &str, enabled: bool) -> Result<TestScope<TestConfig>, Error> {
+    TestScope::start(FakeRuntime::new(), config(tag, enabled), |scope| {
+        scope.add_image(Tagged::default())?;
+        Ok(())
+    })
+}
+
+#[test]
+fn test_image_reference() {
+    assert_eq!(
+        image_reference("quay.io/tarilabs", "tor", "latest"),
+        "quay.io/tarilabs/tor:latest"
+    );
+    assert_eq!(image_reference("", "grafana", "9.1.0"), "grafana:9.1.0");
+    assert_eq!(
+        image_reference("localhost:5000", "tor", DIGEST),
+        format!("localhost:5000/tor@{}", DIGEST)
+    );
+    assert_eq!(
+        image_reference("localhost:5000", "tor", &format!("@{}", DIGEST)),
+        format!("localhost:5000/tor@{}", DIGEST)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_tag_change_pulls_and_recreates() -> Result<(), Error> {
+    let mut test = start("v1", true)?;
+    test.wait_for_status(&Tagged::id(), TaskStatus::is_ready).await?;
+    assert!(test.runtime.has_image("test/tagged:v1"));
+
+    test.scope.set_config(Some(config(DIGEST, true)))?;
+    test.wait_for_status(&Tagged::id(), |status| !status.is_ready()).await?;
+    test.wait_for_status(&Tagged::id(), TaskStatus::is_ready).await?;
+    assert!(test.runtime.has_image(&format!("test/tagged@{}", DIGEST)));
+    assert_eq!(test.runtime.container("test_tagged"), Some(true));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_disabled_image_is_not_pulled() -> Result<(), Error> {
+    let mut test = start("v1", false)?;
+    test.wait_for_status(&Tagged::id(), TaskStatus::is_unavailable).await?;
+    assert!(!test.runtime.has_image("test/tagged:v1"));
+
+    test.scope.set_config(Some(config("v1", true)))?;
+    test.wait_for_status(&Tagged::id(), TaskStatus::is_ready).await?;
+    assert!(test.runtime.has_image("test/tagged:v1"));
+    Ok(())
+}
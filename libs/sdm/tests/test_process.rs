@@ -0,0 +1,100 @@
+mod common;
+
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Error;
+use common::{TestProtocol, TestScope};
+use tari_launchpad_protocol::container::TaskStatus;
+use tari_sdm::{
+    ids::{ManagedTask, TaskId},
+    image::{Args, ManagedContainer},
+    policy::{Backoff, RestartPolicy},
+    process::ManagedProcess,
+    runtime::FakeRuntime,
+};
+use tokio::time::timeout;
+
+#[derive(Debug)]
+struct Shell {
+    program: &'static str,
+    script: &'static str,
+}
+
+impl ManagedTask for Shell {
+    fn id() -> TaskId {
+        "Shell".into()
+    }
+
+    fn restart_policy() -> RestartPolicy {
+        RestartPolicy::OnFailure(Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            max_attempts: Some(1),
+        })
+    }
+}
+
+impl ManagedContainer for Shell {
+    type Protocol = TestProtocol;
+
+    fn reconfigure(&mut self, config: Option<&bool>) -> Option<bool> {
+        config.copied()
+    }
+
+    fn registry(&self) -> &str {
+        "test"
+    }
+
+    fn image_name(&self) -> &str {
+        "shell"
+    }
+
+    fn args(&self, args: &mut Args) {
+        args.flag("-c");
+        args.flag(self.script);
+    }
+}
+
+impl ManagedProcess for Shell {
+    fn program(&self) -> PathBuf {
+        self.program.into()
+    }
+}
+
+fn start(program: &'static str, script: &'static str) -> Result<TestScope, Error> {
+    TestScope::start(FakeRuntime::new(), true, |scope| {
+        scope.add_process(Shell { program, script })?;
+        Ok(())
+    })
+}
+
+#[tokio::test]
+async fn test_logs_and_stop() -> Result<(), Error> {
+    let mut test = start("sh", "echo started; exec sleep 1000")?;
+    test.wait_for_status(&Shell::id(), TaskStatus::is_ready).await?;
+    test.wait_for(&Shell::id(), |state| state.tail.iter().any(|line| line == "started"))
+        .await?;
+    timeout(Duration::from_secs(30), test.scope.stop()).await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_exit_code() -> Result<(), Error> {
+    let mut test = start("sh", "exit 3")?;
+    let state = test
+        .wait_for_status(&Shell::id(), |status| {
+            matches!(status, TaskStatus::Failed { next_retry: None, .. })
+        })
+        .await?;
+    assert_eq!(state.exits, 1);
+    assert_eq!(state.last_exit.and_then(|exit| exit.exit_code), Some(3));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_program() -> Result<(), Error> {
+    let mut test = start("no_such_program_for_sdm", "")?;
+    let state = test.wait_for_status(&Shell::id(), TaskStatus::is_failed).await?;
+    assert_eq!(state.exits, 0);
+    Ok(())
+}
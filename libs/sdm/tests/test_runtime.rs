@@ -0,0 +1,161 @@
+mod common;
+
+use std::time::Duration;
+
+use anyhow::Error;
+use bollard::models::EventMessageTypeEnum;
+use common::{TestProtocol, TestScope};
+use tari_launchpad_protocol::container::TaskStatus;
+use tari_sdm::{
+    ids::{ManagedTask, TaskId},
+    image::{ManagedContainer, Networks},
+    network::ManagedNetwork,
+    policy::{Backoff, RestartPolicy},
+    runtime::FakeRuntime,
+};
+
+const CONTAINER: &str = "test_echo";
+const NETWORK: &str = "test_net";
+
+#[derive(Debug, Default)]
+struct Net;
+
+impl ManagedTask for Net {
+    fn id() -> TaskId {
+        "Net".into()
+    }
+}
+
+impl ManagedNetwork for Net {
+    type Protocol = TestProtocol;
+
+    fn network_name(&self) -> &str {
+        "net"
+    }
+}
+
+#[derive(Debug, Default)]
+struct Echo;
+
+impl ManagedTask for Echo {
+    fn id() -> TaskId {
+        "Echo".into()
+    }
+
+    fn deps() -> Vec<TaskId> {
+        vec![Net::id()]
+    }
+
+    fn restart_policy() -> RestartPolicy {
+        RestartPolicy::OnFailure(Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            max_attempts: Some(2),
+        })
+    }
+}
+
+impl ManagedContainer for Echo {
+    type Protocol = TestProtocol;
+
+    fn reconfigure(&mut self, config: Option<&bool>) -> Option<bool> {
+        config.copied()
+    }
+
+    fn registry(&self) -> &str {
+        "test"
+    }
+
+    fn image_name(&self) -> &str {
+        "echo"
+    }
+
+    fn networks(&self, networks: &mut Networks) {
+        networks.add("echo", Net::id());
+    }
+}
+
+fn start(runtime: FakeRuntime) -> Result<TestScope, Error> {
+    TestScope::start(runtime, true, |scope| {
+        scope.add_network(Net::default())?;
+        scope.add_image(Echo::default())?;
+        Ok(())
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_start_and_stop() -> Result<(), Error> {
+    let mut test = start(FakeRuntime::new())?;
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+    assert_eq!(test.runtime.container(CONTAINER), Some(true));
+    assert!(test.runtime.has_network(NETWORK));
+
+    test.scope.stop().await?;
+    assert_eq!(test.runtime.container(CONTAINER), None);
+    assert!(!test.runtime.has_network(NETWORK));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_after_crash() -> Result<(), Error> {
+    let mut test = start(FakeRuntime::new())?;
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+
+    test.runtime.exit(CONTAINER, 1);
+    let state = test.wait_for_status(&Echo::id(), TaskStatus::is_failed).await?;
+    let exit = state.last_exit.expect("exit is not recorded");
+    assert_eq!(exit.exit_code, Some(1));
+    assert!(matches!(state.status, TaskStatus::Failed {
+        attempts: 1,
+        next_retry: Some(_),
+        ..
+    }));
+
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+    assert_eq!(test.runtime.container(CONTAINER), Some(true));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_attempts_reset_after_start() -> Result<(), Error> {
+    let mut test = start(FakeRuntime::new())?;
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+    test.runtime.oom(CONTAINER);
+    let state = test.wait_for_status(&Echo::id(), TaskStatus::is_failed).await?;
+    assert!(state.last_exit.map(|exit| exit.oom_killed).unwrap_or_default());
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+
+    test.runtime.exit(CONTAINER, 2);
+    let state = test.wait_for_status(&Echo::id(), TaskStatus::is_failed).await?;
+    assert_eq!(state.exits, 2);
+    assert!(matches!(state.status, TaskStatus::Failed { attempts: 1, .. }));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_lost_events() -> Result<(), Error> {
+    let runtime = FakeRuntime::new();
+    // The task doesn't know the container was created and started
+    runtime.mute(EventMessageTypeEnum::CONTAINER, true);
+    let mut test = start(runtime)?;
+    test.wait_for_status(&Echo::id(), TaskStatus::is_ready).await?;
+    assert_eq!(test.runtime.container(CONTAINER), Some(true));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_broken_image() -> Result<(), Error> {
+    let runtime = FakeRuntime::new();
+    runtime.break_image("test/echo:latest");
+    let mut test = start(runtime)?;
+    // Gives up after the second attempt
+    let state = test
+        .wait_for_status(&Echo::id(), |status| {
+            matches!(status, TaskStatus::Failed { next_retry: None, .. })
+        })
+        .await?;
+    assert!(matches!(state.status, TaskStatus::Failed { attempts: 2, .. }));
+    assert!(state.last_exit.is_none());
+    assert_eq!(test.runtime.container(CONTAINER), None);
+    Ok(())
+}
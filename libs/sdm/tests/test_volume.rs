@@ -0,0 +1,72 @@
+mod common;
+
+use anyhow::Error;
+use common::{TestProtocol, TestScope};
+use tari_launchpad_protocol::container::TaskStatus;
+use tari_sdm::{
+    ids::{ManagedTask, TaskId},
+    runtime::FakeRuntime,
+    volume::ManagedVolume,
+};
+
+const VOLUME: &str = "test_data";
+
+#[derive(Debug, Default)]
+struct Data {
+    enabled: Option<bool>,
+}
+
+impl ManagedTask for Data {
+    fn id() -> TaskId {
+        "Data".into()
+    }
+}
+
+impl ManagedVolume for Data {
+    type Protocol = TestProtocol;
+
+    fn reconfigure(&mut self, config: Option<&bool>) -> bool {
+        self.enabled = config.copied();
+        self.enabled.unwrap_or_default()
+    }
+
+    fn is_available(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    fn volume_name(&self) -> &str {
+        "data"
+    }
+}
+
+/// The config enables the volume.
+fn start(enabled: bool) -> Result<TestScope, Error> {
+    TestScope::start(FakeRuntime::new(), enabled, |scope| {
+        scope.add_volume(Data::default())?;
+        Ok(())
+    })
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_disabled_volume_is_not_created() -> Result<(), Error> {
+    let mut test = start(false)?;
+    test.wait_for_status(&Data::id(), TaskStatus::is_unavailable).await?;
+    assert!(!test.runtime.has_volume(VOLUME));
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_volume_removed_when_disabled() -> Result<(), Error> {
+    let mut test = start(true)?;
+    test.wait_for_status(&Data::id(), TaskStatus::is_ready).await?;
+    assert!(test.runtime.has_volume(VOLUME));
+
+    test.scope.set_config(Some(false))?;
+    test.wait_for_status(&Data::id(), TaskStatus::is_unavailable).await?;
+    assert!(!test.runtime.has_volume(VOLUME));
+
+    test.scope.set_config(Some(true))?;
+    test.wait_for_status(&Data::id(), TaskStatus::is_ready).await?;
+    assert!(test.runtime.has_volume(VOLUME));
+    Ok(())
+}